@@ -1,29 +1,47 @@
 use anyhow::Result;
+use clap::Parser;
 use n9_chat_telegram::TelegramParticle;
-use n9_core::Substance;
+use n9_core::{ConfigArgs, Substance};
 use n9_exchange_dydx::DyDxParticle;
 // use n9_model_anthropic::AnthropicParticle;
 use n9_app_stdio::StdioApp;
 use n9_app_tui::TuiApp;
 use n9_control_chat::ChatParticle;
+use n9_control_scheduler::SchedulerParticle;
+use n9_model_mesh::{ModelMeshParticle, ModelProviderParticle};
 use n9_model_openai::OpenAIParticle;
+use n9_tool_substance::{SubstanceProviderParticle, SubstanceToolParticle};
 use ui9_mesh::Mesh;
 
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = Args::parse();
     env_logger::try_init()?;
     Mesh::activate().await?;
-    let mut substance = Substance::arise();
+    let mut substance = Substance::arise_with(args.config.stack()?);
     // TODO: Rename to *Model
     substance.add_particle::<OpenAIParticle>()?;
     // substance.add_particle::<AnthropicParticle>()?;
 
+    // Sharing models between substances of the mesh
+    substance.add_particle::<ModelProviderParticle>()?;
+    substance.add_particle::<ModelMeshParticle>()?;
+
     // TODO: Rename to *Exchange
     substance.add_particle::<DyDxParticle>()?;
 
     // TODO: Rename to *Control
     substance.add_particle::<ChatParticle>()?;
 
+    substance.add_particle::<SchedulerParticle>()?;
+
     // substance.add_particle::<StdioApp>()?;
 
     substance.add_particle::<TuiApp>()?;
@@ -31,6 +49,10 @@ async fn main() -> Result<()> {
     // TODO: Rename to *Chat
     substance.add_particle::<TelegramParticle>()?;
 
+    // Delegating questions between substances of the mesh
+    substance.add_particle::<SubstanceProviderParticle>()?;
+    substance.add_particle::<SubstanceToolParticle>()?;
+
     // Stdio is not compatible with tracing and will be replaced with DUI
     // substance.add_particle::<StdioParticle>()?;
     substance.join().await?;
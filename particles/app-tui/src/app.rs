@@ -111,6 +111,7 @@ struct Terminate;
 impl DoAsync<Terminate> for TuiApp {
     async fn handle(&mut self, _: Terminate, ctx: &mut Context<Self>) -> Result<Next<Self>> {
         ctx.shutdown();
+        self.substance.bond(&ctx).done()?;
         self.substance.substance.interrupt()?;
         ratatui::try_restore()?;
         Ok(Next::done())
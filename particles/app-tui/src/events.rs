@@ -1,22 +1,24 @@
-use crate::app::TuiApp;
 use anyhow::Result;
-use crb::agent::{Address, Agent, AgentSession, DoSync, Next, ToAddress};
-use crossterm::event;
+use crb::agent::{Address, Agent, AgentSession, DoSync, Next, OnEvent, ToAddress};
+use crossterm::event::{self, Event};
 use std::time::Duration;
 
-pub struct EventsDrainer {
-    app: Address<TuiApp>,
+pub struct EventsDrainer<A: Agent> {
+    app: Address<A>,
 }
 
-impl EventsDrainer {
-    pub fn new(app: impl ToAddress<TuiApp>) -> Self {
+impl<A: Agent> EventsDrainer<A> {
+    pub fn new(app: impl ToAddress<A>) -> Self {
         Self {
             app: app.to_address(),
         }
     }
 }
 
-impl Agent for EventsDrainer {
+impl<A> Agent for EventsDrainer<A>
+where
+    A: OnEvent<Event>,
+{
     type Context = AgentSession<Self>;
 
     fn begin(&mut self) -> Next<Self> {
@@ -24,7 +26,10 @@ impl Agent for EventsDrainer {
     }
 }
 
-impl DoSync for EventsDrainer {
+impl<A> DoSync for EventsDrainer<A>
+where
+    A: OnEvent<Event>,
+{
     fn repeat(&mut self, _: &mut ()) -> Result<Option<Next<Self>>> {
         if event::poll(Duration::from_millis(1_000))? {
             let event = event::read()?;
@@ -1,7 +1,9 @@
 mod app;
 mod events;
 mod layouts;
+mod maker;
 mod state;
 mod widgets;
 
 pub use app::TuiApp;
+pub use maker::MakerTui;
@@ -0,0 +1,151 @@
+use crate::events::EventsDrainer;
+use crate::layouts::{AutoLayout, TabLayout};
+use crate::widgets::{Component, FlowTree, FocusControl, PeerList, Render, SharedTrees};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use crb::agent::{Agent, Context, DoAsync, DoSync, ManagedContext, Next, OnEvent, RunAgent};
+use crb::core::Slot;
+use crb::runtime::InterruptionLevel;
+use crb::superagent::{Interval, StreamSession, Supervisor, SupervisorSession, Tick};
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::prelude::Direction;
+use ratatui::DefaultTerminal;
+use ui9_maker::{AppLink, UiEvent};
+
+/// A terminal dashboard of the mesh that works without a substance.
+pub struct MakerTui {
+    link: AppLink,
+    terminal: Slot<DefaultTerminal>,
+    trees: SharedTrees,
+    dashboard: Box<dyn Render>,
+    focus: FocusControl,
+    interval: Interval,
+}
+
+impl MakerTui {
+    pub fn new(link: AppLink) -> Self {
+        let trees = SharedTrees::default();
+        let main = AutoLayout::new(
+            Direction::Horizontal,
+            [
+                (PeerList::new().widget(), 1),
+                (FlowTree::new(trees.clone()).widget(), 2),
+            ],
+        )
+        .widget();
+        let mut focus = FocusControl::new();
+        focus.set(&*main);
+        let dashboard = TabLayout::new("Nine Maker".into(), [(main, "Mesh".to_string())]).widget();
+        Self {
+            link,
+            terminal: Slot::empty(),
+            trees,
+            dashboard,
+            focus,
+            interval: Interval::new(),
+        }
+    }
+}
+
+impl Supervisor for MakerTui {
+    type BasedOn = StreamSession<Self>;
+    type GroupBy = ();
+}
+
+impl Agent for MakerTui {
+    type Context = SupervisorSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+}
+
+struct Initialize;
+
+#[async_trait]
+impl DoAsync<Initialize> for MakerTui {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        let terminal = ratatui::try_init()?;
+        self.terminal.fill(terminal)?;
+
+        let drainer = EventsDrainer::new(&ctx);
+        let mut runtime = RunAgent::new(drainer);
+        runtime.level = InterruptionLevel::ABORT;
+        ctx.spawn_runtime(runtime, ());
+
+        ctx.consume(self.link.drainer()?);
+
+        self.interval.set_interval_ms(200)?;
+        ctx.consume(self.interval.events()?);
+
+        Ok(Next::do_sync(Render))
+    }
+}
+
+#[async_trait]
+impl OnEvent<Event> for MakerTui {
+    async fn handle(&mut self, event: Event, ctx: &mut Context<Self>) -> Result<()> {
+        let mut next_state = Next::do_sync(Render);
+        if let Event::Key(event) = event {
+            self.dashboard.handle(event, &mut self.focus);
+            if event.modifiers.contains(KeyModifiers::CONTROL) {
+                if let KeyCode::Char('q') | KeyCode::Char('w') = event.code {
+                    next_state = Next::do_async(Terminate);
+                }
+            }
+        }
+        ctx.do_next(next_state);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OnEvent<UiEvent> for MakerTui {
+    async fn handle(&mut self, event: UiEvent, _ctx: &mut Context<Self>) -> Result<()> {
+        let mut trees = self
+            .trees
+            .lock()
+            .map_err(|_| anyhow!("The trees are poisoned"))?;
+        match event {
+            UiEvent::SetTree { peer, tree } => {
+                trees.insert(peer, tree);
+            }
+            UiEvent::DelTree { peer } => {
+                trees.remove(&peer);
+            }
+            // Peers are rendered by the peer list itself
+            UiEvent::SetState { .. } | UiEvent::StateChanged => {}
+        }
+        Ok(())
+    }
+}
+
+struct Render;
+
+impl DoSync<Render> for MakerTui {
+    fn once(&mut self, _: &mut Render) -> Result<Next<Self>> {
+        let terminal = self.terminal.get_mut()?;
+        terminal.draw(|frame| self.dashboard.render(&frame.area(), frame.buffer_mut()))?;
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl OnEvent<Tick> for MakerTui {
+    async fn handle(&mut self, _: Tick, ctx: &mut Context<Self>) -> Result<()> {
+        ctx.do_next(Next::do_sync(Render));
+        Ok(())
+    }
+}
+
+struct Terminate;
+
+#[async_trait]
+impl DoAsync<Terminate> for MakerTui {
+    async fn handle(&mut self, _: Terminate, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        ctx.shutdown();
+        self.link.address.interrupt()?;
+        ratatui::try_restore()?;
+        Ok(Next::done())
+    }
+}
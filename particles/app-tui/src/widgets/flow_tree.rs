@@ -0,0 +1,86 @@
+use crate::widgets::{Component, FocusControl, Reason};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{List, ListItem, Widget},
+};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use ui9_dui::tracers::tree::{Level, Tree};
+use ui9_dui::State;
+use ui9_net::tracers::peer::PeerId;
+
+/// Flow trees of peers shared between the app and the widget.
+pub type SharedTrees = Arc<Mutex<BTreeMap<PeerId, State<Tree>>>>;
+
+/// Browses flow trees of peers, arrows switch the peer.
+pub struct FlowTree {
+    trees: SharedTrees,
+    selected: usize,
+}
+
+impl FlowTree {
+    pub fn new(trees: SharedTrees) -> Self {
+        Self { trees, selected: 0 }
+    }
+}
+
+impl Component for FlowTree {
+    fn title(&self) -> Option<&str> {
+        Some("Flows")
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) -> Result<(), Reason> {
+        let trees = self.trees.lock().map_err(|_| "The trees are poisoned")?;
+        if trees.is_empty() {
+            return Err("No flow trees discovered yet".into());
+        }
+        let selected = self.selected.min(trees.len() - 1);
+        let (peer, tree) = trees.iter().nth(selected).ok_or("No peer selected")?;
+
+        let header = format!("◀ {peer} ({}/{}) ▶", selected + 1, trees.len());
+        let mut items = vec![ListItem::new(Line::from(Span::styled(
+            header,
+            Style::default().fg(Color::Yellow),
+        )))];
+        render_level(&tree.borrow().root, 0, &mut items);
+
+        let list = List::new(items);
+        list.render(area, buf);
+        Ok(())
+    }
+
+    fn handle(&mut self, event: KeyEvent, _ctrl: &mut FocusControl) {
+        let total = self.trees.lock().map(|trees| trees.len()).unwrap_or(0);
+        if total == 0 {
+            return;
+        }
+        let selected = self.selected.min(total - 1);
+        self.selected = match event.code {
+            KeyCode::Left => selected.checked_sub(1).unwrap_or(total - 1),
+            KeyCode::Right => (selected + 1) % total,
+            _ => selected,
+        };
+    }
+}
+
+fn render_level(level: &Level, depth: usize, items: &mut Vec<ListItem<'static>>) {
+    for (name, sublevel) in &level.levels {
+        let mut spans = vec![
+            Span::from("  ".repeat(depth + 1)),
+            Span::styled(name.clone(), Style::default().fg(Color::White)),
+        ];
+        if let Some(info) = sublevel.tracer_info.as_ref() {
+            spans.push(Span::from(" "));
+            spans.push(Span::styled(
+                info.class.clone(),
+                Style::default().fg(Color::Blue),
+            ));
+        }
+        items.push(ListItem::new(Line::from(spans)));
+        render_level(sublevel, depth + 1, items);
+    }
+}
@@ -23,4 +23,9 @@ impl Client {
             .await?;
         Ok(())
     }
+
+    pub async fn send_text(&mut self, chat_id: i64, text: String) -> Result<()> {
+        self.bot.send_message(ChatId(chat_id), text).await?;
+        Ok(())
+    }
 }
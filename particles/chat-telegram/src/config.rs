@@ -1,4 +1,4 @@
-use n9_core::Config;
+use n9_core::{Config, FieldDoc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize)]
@@ -8,6 +8,11 @@ pub struct TelegramConfig {
 
 impl Config for TelegramConfig {
     const NAMESPACE: &str = "telegram";
+    const SECRETS: &[&str] = &["api_key"];
+    const FIELDS: &[FieldDoc] = &[FieldDoc::new(
+        "api_key",
+        "The token of the bot provided by @BotFather",
+    )];
 
     fn template() -> Self {
         Self {
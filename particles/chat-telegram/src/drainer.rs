@@ -3,12 +3,22 @@ use crate::particle::TelegramParticle;
 use anyhow::Result;
 use async_trait::async_trait;
 use crb::agent::{Address, Agent, AgentSession, DoAsync, Next};
+use crb::core::time::{sleep, Duration};
 use teloxide_core::{payloads::GetUpdatesSetters, prelude::Requester, types::UpdateKind};
 
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Reported to the particle when the polling status changes.
+pub enum PollingStatus {
+    Success,
+    Failure(String),
+}
+
 pub struct TelegramDrainer {
     particle: Address<TelegramParticle>,
     client: Client,
     offset: i32,
+    healthy: Option<bool>,
 }
 
 impl TelegramDrainer {
@@ -17,6 +27,7 @@ impl TelegramDrainer {
             particle,
             client,
             offset: 0,
+            healthy: None,
         }
     }
 }
@@ -32,11 +43,24 @@ impl Agent for TelegramDrainer {
 #[async_trait]
 impl DoAsync for TelegramDrainer {
     async fn repeat(&mut self, _: &mut ()) -> Result<Option<Next<Self>>> {
-        let updates = self.client.get_updates().offset(self.offset).await?;
-        for update in updates {
-            self.offset = update.id.as_offset();
-            if let UpdateKind::Message(message) = update.kind {
-                self.particle.event(message)?;
+        match self.client.get_updates().offset(self.offset).await {
+            Ok(updates) => {
+                if self.healthy != Some(true) {
+                    self.healthy = Some(true);
+                    self.particle.event(PollingStatus::Success)?;
+                }
+                for update in updates {
+                    self.offset = update.id.as_offset();
+                    if let UpdateKind::Message(message) = update.kind {
+                        self.particle.event(message)?;
+                    }
+                }
+            }
+            Err(err) => {
+                self.healthy = Some(false);
+                self.particle
+                    .event(PollingStatus::Failure(err.to_string()))?;
+                sleep(RETRY_DELAY).await;
             }
         }
         Ok(None)
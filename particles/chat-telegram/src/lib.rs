@@ -3,4 +3,6 @@ mod config;
 mod drainer;
 mod particle;
 
+pub use client::Client;
+pub use config::TelegramConfig;
 pub use particle::TelegramParticle;
@@ -1,6 +1,6 @@
 use crate::client::Client;
 use crate::config::TelegramConfig;
-use crate::drainer::TelegramDrainer;
+use crate::drainer::{PollingStatus, TelegramDrainer};
 use anyhow::Result;
 use async_trait::async_trait;
 use crb::agent::{Agent, Context, DoAsync, Next, OnEvent};
@@ -9,8 +9,8 @@ use crb::superagent::{
     Entry, Interval, OnResponse, Output, StreamSession, Supervisor, SupervisorSession, Tick,
 };
 use n9_core::{
-    ChatRequest, ChatResponse, ConfigSegmentUpdates, Particle, SubstanceBond, SubstanceLinks,
-    UpdateConfig,
+    ChatRequest, ChatResponse, ConfigSegmentUpdates, HealthCell, HealthStatus, Particle,
+    SubstanceBond, SubstanceLinks, UpdateConfig,
 };
 use std::collections::HashSet;
 use teloxide_core::{
@@ -18,6 +18,8 @@ use teloxide_core::{
     types::{ChatId, Message},
 };
 
+const NOT_CONFIGURED: &str = "The bot is not configured";
+
 pub struct TelegramParticle {
     substance: SubstanceLinks,
     config_updates: Option<Entry<ConfigSegmentUpdates>>,
@@ -27,6 +29,7 @@ pub struct TelegramParticle {
 
     typing: HashSet<ChatId>,
     interval: Interval,
+    health: HealthCell,
 }
 
 impl Particle for TelegramParticle {
@@ -38,6 +41,7 @@ impl Particle for TelegramParticle {
             client: Slot::empty(),
             typing: HashSet::new(),
             interval: Interval::default(),
+            health: HealthCell::new(HealthStatus::unhealthy(NOT_CONFIGURED)),
         }
     }
 }
@@ -61,6 +65,7 @@ struct Initialize;
 impl DoAsync<Initialize> for TelegramParticle {
     async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
         let mut bond = self.substance.bond(&ctx);
+        bond.add_health(&self.health)?;
         let (config, entry) = bond.live_config_updates().await?;
         self.config_updates = Some(entry);
         self.update_config(config, ctx).await?;
@@ -80,12 +85,15 @@ impl UpdateConfig<TelegramConfig> for TelegramParticle {
         ctx: &mut Context<Self>,
     ) -> Result<()> {
         if self.client.is_filled() {
+            self.health.set(HealthStatus::unhealthy(NOT_CONFIGURED));
             self.client.take()?;
             ctx.tracker.terminate_group(());
         }
 
         let client = Client::new(&config.api_key);
         client.get_me().await?;
+        self.health
+            .set(HealthStatus::degraded("Polling is not started"));
         self.client.fill(client)?;
 
         let client = self.client.cloned()?;
@@ -150,3 +158,15 @@ impl OnEvent<Tick> for TelegramParticle {
         Ok(())
     }
 }
+
+#[async_trait]
+impl OnEvent<PollingStatus> for TelegramParticle {
+    async fn handle(&mut self, status: PollingStatus, _ctx: &mut Context<Self>) -> Result<()> {
+        let status = match status {
+            PollingStatus::Success => HealthStatus::Healthy,
+            PollingStatus::Failure(reason) => HealthStatus::unhealthy(reason),
+        };
+        self.health.set(status);
+        Ok(())
+    }
+}
@@ -17,6 +17,12 @@ impl ChatSub {
         let event = ChatAction::Request { question };
         self.listener.action(event);
     }
+
+    /// Adds an answered request to the chat without reasoning.
+    pub fn post(&mut self, question: String, answer: String) {
+        let event = ChatAction::Post { question, answer };
+        self.listener.action(event);
+    }
 }
 
 #[derive(Deref, DerefMut, From, Into)]
@@ -91,4 +97,5 @@ pub enum ChatEvent {
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub enum ChatAction {
     Request { question: String },
+    Post { question: String, answer: String },
 }
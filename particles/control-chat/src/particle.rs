@@ -51,6 +51,10 @@ impl OnEvent<Act<Chat>> for ChatParticle {
                 let ask = SendRequest { question };
                 ctx.do_next(Next::do_async(ask));
             }
+            ChatAction::Post { question, answer } => {
+                self.chat.add(question, Role::Request);
+                self.chat.add(answer, Role::Response);
+            }
         }
         Ok(())
     }
@@ -0,0 +1,123 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use crb::core::time::Duration;
+use n9_core::{Config, FieldDoc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Deserialize, Serialize)]
+pub struct SchedulerConfig {
+    pub entries: Vec<ScheduleEntry>,
+}
+
+impl Config for SchedulerConfig {
+    const NAMESPACE: &str = "scheduler";
+    const FIELDS: &[FieldDoc] = &[FieldDoc::new(
+        "entries",
+        "Prompts sent on a schedule. The schedule is a cron expression\n\
+         like { cron = \"0 0 * * * *\" } or an interval like { interval = 3600 }.\n\
+         The destination type is control_chat, telegram with a chat_id or file with a path.",
+    )];
+
+    fn template() -> Self {
+        let entry = ScheduleEntry {
+            name: "btc-summary".into(),
+            enabled: false,
+            prompt: "Summarize the BTC price".into(),
+            schedule: Schedule::Cron("0 0 * * * *".into()),
+            destination: Destination::ControlChat,
+        };
+        Self {
+            entries: vec![entry],
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct ScheduleEntry {
+    pub name: String,
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    pub prompt: String,
+    pub schedule: Schedule,
+    pub destination: Destination,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Schedule {
+    /// A cron expression with seconds, e.g. `0 0 * * * *`
+    Cron(String),
+    /// An interval in seconds
+    Interval(u64),
+}
+
+impl Schedule {
+    /// The delay until the next run.
+    pub fn next_delay(&self) -> Result<Duration> {
+        match self {
+            Self::Cron(expr) => {
+                let schedule = cron::Schedule::from_str(expr)?;
+                let next = schedule
+                    .upcoming(Local)
+                    .next()
+                    .ok_or_else(|| anyhow!("No upcoming runs for {expr}"))?;
+                let delay = (next - Local::now()).to_std().unwrap_or_default();
+                Ok(delay)
+            }
+            Self::Interval(0) => Err(anyhow!("The interval must be positive")),
+            Self::Interval(secs) => Ok(Duration::from_secs(*secs)),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Destination {
+    ControlChat,
+    Telegram { chat_id: i64 },
+    File { path: PathBuf },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entry() {
+        let entry: ScheduleEntry = toml::from_str(
+            r#"
+            name = "report"
+            prompt = "Write a report"
+            schedule = { cron = "0 0 * * * *" }
+            destination = { type = "telegram", chat_id = 42 }
+            "#,
+        )
+        .unwrap();
+        assert!(entry.enabled);
+        assert!(matches!(entry.schedule, Schedule::Cron(ref expr) if expr == "0 0 * * * *"));
+        assert!(matches!(
+            entry.destination,
+            Destination::Telegram { chat_id: 42 }
+        ));
+
+        let schedule: Schedule = toml::from_str("interval = 60").unwrap();
+        assert!(matches!(schedule, Schedule::Interval(60)));
+    }
+
+    #[test]
+    fn test_next_delay() {
+        let delay = Schedule::Interval(60).next_delay().unwrap();
+        assert_eq!(delay, Duration::from_secs(60));
+        assert!(Schedule::Interval(0).next_delay().is_err());
+
+        let hourly = Schedule::Cron("0 0 * * * *".into());
+        assert!(hourly.next_delay().unwrap() <= Duration::from_secs(3600));
+        assert!(Schedule::Cron("not a cron".into()).next_delay().is_err());
+    }
+}
@@ -0,0 +1,5 @@
+mod config;
+mod particle;
+
+pub use config::{Destination, Schedule, ScheduleEntry, SchedulerConfig};
+pub use particle::SchedulerParticle;
@@ -0,0 +1,276 @@
+use crate::config::{Destination, ScheduleEntry, SchedulerConfig};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::Local;
+use crb::agent::{Agent, Context, DoAsync, Next, OnEvent};
+use crb::core::Slot;
+use crb::superagent::{Entry, OnResponse, Output, StreamSession, Supervisor, SupervisorSession};
+use n9_chat_telegram::{Client, TelegramConfig};
+use n9_control_chat::Chat;
+use n9_core::{
+    ChatRequest, ChatResponse, ConfigSegmentUpdates, Particle, SubstanceBond, SubstanceLinks,
+    UpdateConfig,
+};
+use std::collections::HashMap;
+use tokio::fs::OpenOptions;
+use tokio::io::AsyncWriteExt;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use ui9_dui::reporter::Reporter;
+use ui9_dui::{Operation, Sub};
+
+pub struct SchedulerParticle {
+    substance: SubstanceLinks,
+    config_updates: Option<Entry<ConfigSegmentUpdates>>,
+    bond: Slot<SubstanceBond<Self>>,
+
+    entries: Vec<ScheduleEntry>,
+    /// Incremented on every config update to discard outdated triggers
+    generation: u64,
+    /// Pending triggers by indices of entries, aborted when the schedule is replaced
+    timers: HashMap<usize, JoinHandle<()>>,
+    runs: HashMap<RunId, Run>,
+    next_run: u64,
+    chat: Sub<Chat>,
+    /// Subscribed only if some prompt is delivered to Telegram
+    telegram_updates: Option<Entry<ConfigSegmentUpdates>>,
+    telegram: Option<Client>,
+}
+
+impl Particle for SchedulerParticle {
+    fn construct(substance: SubstanceLinks) -> Self {
+        Self {
+            substance,
+            config_updates: None,
+            bond: Slot::empty(),
+            entries: Vec::new(),
+            generation: 0,
+            timers: HashMap::new(),
+            runs: HashMap::new(),
+            next_run: 0,
+            chat: Sub::local_unified_actions(),
+            telegram_updates: None,
+            telegram: None,
+        }
+    }
+}
+
+impl Supervisor for SchedulerParticle {
+    type BasedOn = StreamSession<Self>;
+    type GroupBy = ();
+}
+
+impl Agent for SchedulerParticle {
+    type Context = SupervisorSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+
+    fn end(&mut self) {
+        self.cancel_timers();
+    }
+}
+
+struct Initialize;
+
+#[async_trait]
+impl DoAsync<Initialize> for SchedulerParticle {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        let mut bond = self.substance.bond(&ctx);
+        let (config, entry) = bond.live_config_updates().await?;
+        self.config_updates = Some(entry);
+        self.update_config(config, ctx).await?;
+        self.bond.fill(bond)?;
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl UpdateConfig<SchedulerConfig> for SchedulerParticle {
+    async fn update_config(
+        &mut self,
+        config: SchedulerConfig,
+        ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.generation += 1;
+        self.cancel_timers();
+        self.entries = config.entries;
+        self.update_telegram(ctx).await;
+        for index in 0..self.entries.len() {
+            if self.entries[index].enabled {
+                self.schedule(index, ctx);
+            }
+        }
+        log::info!("Scheduled prompts: {}", self.entries.len());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UpdateConfig<TelegramConfig> for SchedulerParticle {
+    async fn update_config(
+        &mut self,
+        config: TelegramConfig,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.telegram = Some(Client::new(&config.api_key));
+        Ok(())
+    }
+}
+
+impl SchedulerParticle {
+    /// Follows the Telegram config while some prompt is delivered to Telegram.
+    /// If the config can't be loaded, only prompts delivered to Telegram fail.
+    async fn update_telegram(&mut self, ctx: &mut Context<Self>) {
+        let telegram_entries: Vec<_> = self
+            .entries
+            .iter()
+            .filter(|entry| matches!(entry.destination, Destination::Telegram { .. }))
+            .map(|entry| entry.name.clone())
+            .collect();
+        if telegram_entries.is_empty() {
+            self.telegram_updates = None;
+            self.telegram = None;
+            return;
+        }
+        if self.telegram_updates.is_some() {
+            return;
+        }
+        let mut bond = self.substance.bond(&ctx);
+        match bond.live_config_updates::<TelegramConfig>().await {
+            Ok((config, entry)) => {
+                self.telegram_updates = Some(entry);
+                self.telegram = Some(Client::new(&config.api_key));
+            }
+            Err(err) => {
+                for name in telegram_entries {
+                    let message = format!("Can't deliver the prompt {name} to Telegram: {err}");
+                    log::error!("{message}");
+                    Reporter::failure(&message);
+                }
+            }
+        }
+    }
+
+    fn cancel_timers(&mut self) {
+        for (_, timer) in self.timers.drain() {
+            timer.abort();
+        }
+    }
+
+    fn schedule(&mut self, index: usize, ctx: &mut Context<Self>) {
+        let entry = &self.entries[index];
+        match entry.schedule.next_delay() {
+            Ok(delay) => {
+                let trigger = Trigger {
+                    generation: self.generation,
+                    index,
+                };
+                let address = ctx.address().clone();
+                let timer = tokio::spawn(async move {
+                    sleep(delay).await;
+                    address.event(trigger).ok();
+                });
+                self.timers.insert(index, timer);
+            }
+            Err(err) => {
+                let message = format!("Can't schedule the prompt {}: {err}", entry.name);
+                log::error!("{message}");
+                Reporter::failure(&message);
+            }
+        }
+    }
+}
+
+struct Trigger {
+    generation: u64,
+    index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct RunId(u64);
+
+struct Run {
+    entry: ScheduleEntry,
+    operation: Operation,
+}
+
+#[async_trait]
+impl OnEvent<Trigger> for SchedulerParticle {
+    async fn handle(&mut self, msg: Trigger, ctx: &mut Context<Self>) -> Result<()> {
+        if msg.generation != self.generation {
+            // The schedule was replaced by a new config
+            return Ok(());
+        }
+        self.schedule(msg.index, ctx);
+
+        let entry = self.entries[msg.index].clone();
+        let operation = Operation::start(&format!("Scheduled prompt: {}", entry.name));
+        let request = ChatRequest::user(&entry.prompt);
+        let session = self.substance.router.new_session().await?;
+        let task = session.chat(request);
+
+        let id = RunId(self.next_run);
+        self.next_run += 1;
+        self.runs.insert(id, Run { entry, operation });
+        ctx.assign(task, (), id);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OnResponse<ChatResponse, RunId> for SchedulerParticle {
+    async fn on_response(
+        &mut self,
+        response: Output<ChatResponse>,
+        id: RunId,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        if let Some(mut run) = self.runs.remove(&id) {
+            let res = match response {
+                Ok(response) => self.deliver(&run.entry, response.squash()).await,
+                Err(err) => Err(err.into()),
+            };
+            match res {
+                Ok(()) => {
+                    run.operation.end("Scheduled prompt delivered");
+                }
+                Err(err) => {
+                    log::error!("Scheduled prompt {} failed: {err}", run.entry.name);
+                    // The incomplete operation reports a failure on drop
+                    run.operation.failure(&err.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SchedulerParticle {
+    async fn deliver(&mut self, entry: &ScheduleEntry, answer: String) -> Result<()> {
+        match &entry.destination {
+            Destination::ControlChat => {
+                self.chat.post(entry.prompt.clone(), answer);
+            }
+            Destination::Telegram { chat_id } => {
+                let client = self
+                    .telegram
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("The Telegram client is not configured"))?;
+                client.send_text(*chat_id, answer).await?;
+            }
+            Destination::File { path } => {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await?;
+                let timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+                let record = format!("## {} ({timestamp})\n\n{answer}\n\n", entry.name);
+                file.write_all(record.as_bytes()).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 mod config;
 mod particle;
 
-pub use particle::DyDxParticle;
+pub use config::DyDxConfig;
+pub use particle::{DyDxParticle, Price};
@@ -6,6 +6,7 @@ use crb::core::Slot;
 use crb::superagent::{Entry, Supervisor, SupervisorSession};
 use n9_core::{ConfigSegmentUpdates, Particle, SubstanceBond, SubstanceLinks, Tool, UpdateConfig};
 use serde::Deserialize;
+use serde_json::{json, Value};
 
 pub struct DyDxParticle {
     substance: SubstanceLinks,
@@ -78,6 +79,19 @@ impl Tool<Price> for DyDxParticle {
                 .into(),
         )
     }
+
+    fn parameters() -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "ticker": {
+                    "type": "string",
+                    "description": "The ticker of the market, e.g. BTC-USD",
+                },
+            },
+            "required": ["ticker"],
+        }))
+    }
 }
 
 #[derive(Deserialize)]
@@ -0,0 +1,87 @@
+use crate::convert::{AnthropicMessage, AnthropicTool, Block};
+use anyhow::{anyhow, Result};
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+const MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+
+/// A client of the Anthropic Messages API.
+/// It's cheap to clone and is reused by requests.
+#[derive(Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    model: String,
+    max_tokens: i32,
+}
+
+impl Client {
+    pub fn new(api_key: &str, version: &str, model: String, max_tokens: i32) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        let mut key = HeaderValue::from_str(api_key)?;
+        key.set_sensitive(true);
+        headers.insert("x-api-key", key);
+        headers.insert("anthropic-version", HeaderValue::from_str(version)?);
+        let http = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()?;
+        Ok(Self {
+            http,
+            model,
+            max_tokens,
+        })
+    }
+
+    pub async fn messages(
+        &self,
+        system: Option<String>,
+        messages: Vec<AnthropicMessage>,
+        tools: Vec<AnthropicTool>,
+    ) -> Result<Vec<Block>> {
+        let request = MessagesRequest {
+            model: &self.model,
+            max_tokens: self.max_tokens,
+            system,
+            messages,
+            tools,
+        };
+        let response = self.http.post(MESSAGES_URL).json(&request).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error = response
+                .json::<ErrorResponse>()
+                .await
+                .map(|response| response.error.message)
+                .unwrap_or_else(|_| "Unknown error".into());
+            return Err(anyhow!("Anthropic API error {status}: {error}"));
+        }
+        let response: MessagesResponse = response.json().await?;
+        Ok(response.content)
+    }
+}
+
+#[derive(Serialize)]
+struct MessagesRequest<'a> {
+    model: &'a str,
+    max_tokens: i32,
+    /// Developer messages are not allowed in `messages`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<AnthropicTool>,
+}
+
+#[derive(Deserialize)]
+struct MessagesResponse {
+    content: Vec<Block>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetails,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetails {
+    message: String,
+}
@@ -1,11 +1,11 @@
-use anthropic_sdk::Client;
-use dotenvy::dotenv;
-use n9_core::Config;
+use crate::client::Client;
+use anyhow::Result;
+use n9_core::{Config, FieldDoc};
 use serde::{Deserialize, Serialize};
-use std::env;
 
 #[derive(Deserialize, Serialize)]
 pub struct AnthropicConfig {
+    pub api_key: String,
     pub version: String,
     pub model: String,
     pub max_tokens: i32,
@@ -13,9 +13,20 @@ pub struct AnthropicConfig {
 
 impl Config for AnthropicConfig {
     const NAMESPACE: &str = "anthropic";
+    const SECRETS: &[&str] = &["api_key"];
+    const FIELDS: &[FieldDoc] = &[
+        FieldDoc::new(
+            "api_key",
+            "The Anthropic API key, could be a reference like { env = \"ANTHROPIC_API_KEY\" }",
+        ),
+        FieldDoc::new("version", "The version of the Anthropic API"),
+        FieldDoc::new("model", "The model used for requests"),
+        FieldDoc::new("max_tokens", "The maximum number of tokens to generate"),
+    ];
 
     fn template() -> Self {
         Self {
+            api_key: "API KEY HERE".into(),
             version: "2023-06-01".into(),
             model: "claude-3-opus-20240229".into(),
             max_tokens: 1024,
@@ -24,13 +35,12 @@ impl Config for AnthropicConfig {
 }
 
 impl AnthropicConfig {
-    pub fn extract(&self) -> Result<Client, env::VarError> {
-        dotenv().ok();
-        let api_key = env::var("ANTHROPIC_API_KEY")?;
-
-        let client = Client::new()
-            .auth(api_key.as_str())
-            .version(self.version.as_str());
-        Ok(client)
+    pub fn extract(&self) -> Result<Client> {
+        Client::new(
+            &self.api_key,
+            &self.version,
+            self.model.clone(),
+            self.max_tokens,
+        )
     }
 }
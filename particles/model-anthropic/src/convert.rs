@@ -1,46 +1,196 @@
-use n9_core::{Message as ModelMessage, Role as ModelRole};
+use n9_core::{Message as ModelMessage, Role as ModelRole, ToolCall, ToolInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
-#[derive(serde::Serialize)]
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum AnthropicRole {
     User,
     Assistant,
-    System,
 }
 
-#[derive(serde::Serialize)]
+#[derive(Serialize, Debug)]
 pub struct AnthropicMessage {
     pub role: AnthropicRole,
-    pub content: String,
+    pub content: Vec<Block>,
 }
 
-pub fn message(from: ModelMessage) -> AnthropicMessage {
-    let role = match from.role {
-        ModelRole::User => AnthropicRole::User,
-        ModelRole::Assistant => AnthropicRole::Assistant,
-        ModelRole::Developer => AnthropicRole::System,
-    };
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+    },
+    /// Blocks that are not supported, e.g. thinking
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AnthropicTool {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub input_schema: Value,
+}
 
-    AnthropicMessage {
-        role,
-        content: from.content,
+/// Splits messages into the system prompt and the conversation.
+///
+/// Results of tools are sent in user messages and consecutive messages
+/// of the same role are merged, since the API expects alternating roles.
+pub fn messages(from: Vec<ModelMessage>) -> (Option<String>, Vec<AnthropicMessage>) {
+    let mut system: Vec<String> = Vec::new();
+    let mut messages: Vec<AnthropicMessage> = Vec::new();
+    for message in from {
+        let (role, blocks) = match message.role {
+            ModelRole::Developer => {
+                system.push(message.content);
+                continue;
+            }
+            ModelRole::User => (AnthropicRole::User, vec![text(message.content)]),
+            ModelRole::Assistant => {
+                let mut blocks = Vec::new();
+                if !message.content.is_empty() {
+                    blocks.push(text(message.content));
+                }
+                blocks.extend(message.tool_calls.into_iter().map(tool_use));
+                (AnthropicRole::Assistant, blocks)
+            }
+            ModelRole::Tool => {
+                let block = Block::ToolResult {
+                    tool_use_id: message.tool_call_id.unwrap_or_default(),
+                    content: message.content,
+                };
+                (AnthropicRole::User, vec![block])
+            }
+        };
+        match messages.last_mut() {
+            Some(last) if last.role == role => last.content.extend(blocks),
+            _ => messages.push(AnthropicMessage {
+                role,
+                content: blocks,
+            }),
+        }
     }
+    let system = (!system.is_empty()).then(|| system.join("\n\n"));
+    (system, messages)
 }
 
-pub fn choice(from: &serde_json::Value) -> Option<ModelMessage> {
-    let role_str = from.get("role")?.as_str()?;
-    let role = match role_str {
-        "user" => ModelRole::User,
-        "assistant" => ModelRole::Assistant,
-        "system" => ModelRole::Developer,
-        _ => return None,
-    };
+fn text(text: String) -> Block {
+    Block::Text { text }
+}
 
-    let content = from
-        .get("content")
-        .and_then(serde_json::Value::as_str)?
-        .to_string();
+fn tool_use(call: ToolCall) -> Block {
+    let input = serde_json::from_str(&call.arguments).unwrap_or_else(|_| json!({}));
+    Block::ToolUse {
+        id: call.id,
+        name: call.tool,
+        input,
+    }
+}
+
+/// Tools are named by their ids to find them when they are called.
+pub fn tool(from: &ToolInfo) -> AnthropicTool {
+    let meta = &from.meta.meta;
+    let input_schema = meta
+        .parameters
+        .clone()
+        .unwrap_or_else(|| json!({ "type": "object", "properties": {} }));
+    AnthropicTool {
+        name: from.id.clone(),
+        description: meta.description.clone(),
+        input_schema,
+    }
+}
 
-    let message = ModelMessage { role, content };
-    Some(message)
+/// Collects the text and tool calls of the response into a message.
+pub fn choice(content: Vec<Block>) -> ModelMessage {
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for block in content {
+        match block {
+            Block::Text { text: part } => text.push_str(&part),
+            Block::ToolUse { id, name, input } => {
+                let call = ToolCall {
+                    id,
+                    tool: name,
+                    arguments: input.to_string(),
+                };
+                tool_calls.push(call);
+            }
+            Block::ToolResult { .. } | Block::Other => {}
+        }
+    }
+    ModelMessage {
+        tool_calls,
+        ..ModelMessage::new(ModelRole::Assistant, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_system_and_tool_results() {
+        let call = |id: &str| ToolCall {
+            id: id.into(),
+            tool: "price_0".into(),
+            arguments: "{\"market\":\"BTC-USD\"}".into(),
+        };
+        let history = vec![
+            ModelMessage::new(ModelRole::Developer, "Be brief"),
+            ModelMessage::new(ModelRole::User, "Prices?"),
+            ModelMessage {
+                tool_calls: vec![call("a"), call("b")],
+                ..ModelMessage::new(ModelRole::Assistant, "")
+            },
+            ModelMessage::tool_result("a".into(), "100"),
+            ModelMessage::tool_result("b".into(), "200"),
+        ];
+        let (system, messages) = super::messages(history);
+        assert_eq!(system.as_deref(), Some("Be brief"));
+        let roles: Vec<_> = messages.iter().map(|msg| msg.role).collect();
+        assert_eq!(
+            roles,
+            [
+                AnthropicRole::User,
+                AnthropicRole::Assistant,
+                AnthropicRole::User
+            ]
+        );
+        // Parallel results are sent in a single message
+        assert_eq!(messages[2].content.len(), 2);
+        assert_eq!(
+            messages[1].content[0],
+            Block::ToolUse {
+                id: "a".into(),
+                name: "price_0".into(),
+                input: json!({ "market": "BTC-USD" }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_response_with_tool_use() {
+        let content: Vec<Block> = serde_json::from_value(json!([
+            { "type": "thinking", "thinking": "..." },
+            { "type": "text", "text": "Checking" },
+            { "type": "tool_use", "id": "toolu_1", "name": "price_0", "input": { "market": "ETH-USD" } },
+        ]))
+        .unwrap();
+        let message = choice(content);
+        assert_eq!(message.content, "Checking");
+        assert_eq!(message.tool_calls[0].id, "toolu_1");
+        assert_eq!(message.tool_calls[0].arguments, "{\"market\":\"ETH-USD\"}");
+    }
 }
@@ -1,5 +1,7 @@
+mod client;
 mod config;
 mod convert;
 mod particle;
 
+pub use config::AnthropicConfig;
 pub use particle::AnthropicParticle;
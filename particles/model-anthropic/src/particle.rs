@@ -1,7 +1,7 @@
+use crate::client::Client;
 use crate::config::AnthropicConfig;
 use crate::convert;
-use anthropic_sdk::Client;
-use anyhow::{anyhow, Result};
+use anyhow::Result;
 use async_trait::async_trait;
 use crb::agent::{Agent, AgentSession, Context, DoAsync, Next};
 use crb::core::Slot;
@@ -10,7 +10,6 @@ use n9_core::{
     ConfigSegmentUpdates, Model, Particle, SubstanceBond, SubstanceLinks, ToolingChatRequest,
     ToolingChatResponse, UpdateConfig,
 };
-use serde_json::json;
 
 pub struct AnthropicParticle {
     substance: SubstanceLinks,
@@ -81,33 +80,12 @@ impl OnRequest<ToolingChatRequest> for AnthropicParticle {
         request: ToolingChatRequest,
         _ctx: &mut Context<Self>,
     ) -> Result<ToolingChatResponse> {
-        let client = self.client.take()?;
-
-        let messages: Vec<_> = request.messages.into_iter().map(convert::message).collect();
-        let config = self.substance.config::<AnthropicConfig>().await?;
-
-        let anthropic_request = client
-            .model(config.model.as_str())
-            .messages(&serde_json::to_value(messages)?)
-            .max_tokens(config.max_tokens)
-            .build()?;
-
-        let mut combined_response = String::new();
-        let result = anthropic_request
-            .execute(|text| {
-                combined_response.push_str(&text);
-                async {}
-            })
-            .await?;
-        let client = config.extract()?;
-        self.client.fill(client)?;
-        let response_message = convert::choice(&json!({
-            "role": "assistant",
-            "content": combined_response,
-        }))
-        .ok_or_else(|| anyhow!("Failed to build response"))?;
-
-        let messages = vec![response_message];
+        // The client stays in the slot if the request fails
+        let client = self.client.get_mut()?.clone();
+        let tools = request.tools.iter().map(convert::tool).collect();
+        let (system, messages) = convert::messages(request.messages);
+        let content = client.messages(system, messages, tools).await?;
+        let messages = vec![convert::choice(content)];
         let response = ToolingChatResponse { messages };
         Ok(response)
     }
@@ -0,0 +1,21 @@
+use n9_core::{Config, FieldDoc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct SharingConfig {
+    /// Peers allowed to use the model, others are rejected
+    #[serde(default)]
+    pub peers: Vec<String>,
+}
+
+impl Config for SharingConfig {
+    const NAMESPACE: &str = "sharing";
+    const FIELDS: &[FieldDoc] = &[FieldDoc::new(
+        "peers",
+        "Ids of peers allowed to use the local model, others are rejected",
+    )];
+
+    fn template() -> Self {
+        Self { peers: Vec::new() }
+    }
+}
@@ -0,0 +1,90 @@
+use n9_core::{
+    Message, ToolInfo, ToolMeta, ToolMetaWithId, ToolingChatRequest, ToolingChatResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// `ToolingChatRequest` in a form that could be sent over the mesh.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteRequest {
+    pub messages: Vec<Message>,
+    pub tools: Vec<RemoteTool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteTool {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// JSON schema of parameters
+    pub parameters: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteResponse {
+    pub messages: Vec<Message>,
+}
+
+impl From<ToolingChatRequest> for RemoteRequest {
+    fn from(request: ToolingChatRequest) -> Self {
+        let tools = request
+            .tools
+            .iter()
+            .map(|tool| {
+                let meta = &tool.meta.meta;
+                RemoteTool {
+                    id: tool.id.clone(),
+                    name: meta.name.clone(),
+                    description: meta.description.clone(),
+                    parameters: meta.parameters.as_ref().map(|value| value.to_string()),
+                }
+            })
+            .collect();
+        Self {
+            messages: request.messages,
+            tools,
+        }
+    }
+}
+
+impl From<RemoteRequest> for ToolingChatRequest {
+    fn from(request: RemoteRequest) -> Self {
+        let tools = request
+            .tools
+            .into_iter()
+            .map(|tool| {
+                let meta = ToolMeta {
+                    name: tool.name,
+                    description: tool.description,
+                    parameters: tool
+                        .parameters
+                        .and_then(|value| serde_json::from_str(&value).ok()),
+                };
+                let meta = ToolMetaWithId { id: tool.id, meta };
+                ToolInfo {
+                    meta: Arc::new(meta),
+                }
+            })
+            .collect();
+        Self {
+            messages: request.messages,
+            tools,
+        }
+    }
+}
+
+impl From<ToolingChatResponse> for RemoteResponse {
+    fn from(response: ToolingChatResponse) -> Self {
+        Self {
+            messages: response.messages,
+        }
+    }
+}
+
+impl From<RemoteResponse> for ToolingChatResponse {
+    fn from(response: RemoteResponse) -> Self {
+        Self {
+            messages: response.messages,
+        }
+    }
+}
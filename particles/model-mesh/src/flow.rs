@@ -0,0 +1,52 @@
+use crate::convert::{RemoteRequest, RemoteResponse};
+use derive_more::{Deref, DerefMut, From, Into};
+use serde::{Deserialize, Serialize};
+use ui9::names::Fqn;
+use ui9_dui::{Flow, Listener, Publisher, Subscriber, Tracer, Unified};
+use ui9_net::service::{Service, StreamProtocol};
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct ModelServiceSub {
+    listener: Listener<ModelService>,
+}
+
+impl Subscriber for ModelService {
+    type Driver = ModelServiceSub;
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct ModelServicePub {
+    tracer: Tracer<ModelService>,
+}
+
+impl Publisher for ModelService {
+    type Driver = ModelServicePub;
+}
+
+/// Announces a model that is shared with other peers of the mesh.
+/// Requests and responses are not published, they are sent by the `ModelChatService`.
+#[derive(Clone, Serialize, Deserialize, Default, Debug)]
+pub struct ModelService {}
+
+impl Unified for ModelService {
+    fn fqn() -> Fqn {
+        Fqn::root("@model")
+    }
+}
+
+impl Flow for ModelService {
+    type Event = ();
+    type Action = ();
+
+    fn apply(&mut self, _event: Self::Event) {}
+}
+
+/// Sends a chat request to the shared model of a peer.
+pub struct ModelChatService;
+
+impl Service for ModelChatService {
+    const PROTOCOL: StreamProtocol = StreamProtocol::new("/n9-model");
+
+    type Request = RemoteRequest;
+    type Response = RemoteResponse;
+}
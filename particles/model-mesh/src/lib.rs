@@ -0,0 +1,11 @@
+mod config;
+mod convert;
+mod flow;
+mod provider;
+mod proxy;
+
+pub use config::SharingConfig;
+pub use convert::{RemoteRequest, RemoteResponse, RemoteTool};
+pub use flow::{ModelChatService, ModelService};
+pub use provider::ModelProviderParticle;
+pub use proxy::ModelMeshParticle;
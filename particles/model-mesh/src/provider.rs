@@ -0,0 +1,108 @@
+use crate::config::SharingConfig;
+use crate::convert::{RemoteRequest, RemoteResponse};
+use crate::flow::{ModelChatService, ModelService};
+use anyhow::Result;
+use async_trait::async_trait;
+use crb::agent::{Agent, AgentSession, Context, DoAsync, Next, OnEvent};
+use crb::superagent::Entry;
+use n9_core::{ConfigSegmentUpdates, ModelLink, Particle, SubstanceLinks, UpdateConfig};
+use ui9_dui::Pub;
+use ui9_net::service::{self, AllowedPeers, Incoming};
+
+/// Shares the local model of the substance with allowed peers of the mesh.
+pub struct ModelProviderParticle {
+    substance: SubstanceLinks,
+    config_updates: Option<Entry<ConfigSegmentUpdates>>,
+    service: Option<Pub<ModelService>>,
+    allowed: AllowedPeers,
+}
+
+impl Particle for ModelProviderParticle {
+    fn construct(substance: SubstanceLinks) -> Self {
+        Self {
+            substance,
+            config_updates: None,
+            service: None,
+            allowed: AllowedPeers::default(),
+        }
+    }
+}
+
+impl Agent for ModelProviderParticle {
+    type Context = AgentSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+}
+
+struct Initialize;
+
+#[async_trait]
+impl DoAsync<Initialize> for ModelProviderParticle {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        let mut bond = self.substance.bond(&ctx);
+        let (config, entry) = bond.live_config_updates().await?;
+        self.config_updates = Some(entry);
+        self.update_config(config, ctx).await?;
+
+        let requests = service::serve::<ModelChatService>().await?;
+        ctx.consume(requests);
+        // Peers find the model by the announcement
+        self.service = Some(Pub::unified());
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl UpdateConfig<SharingConfig> for ModelProviderParticle {
+    async fn update_config(
+        &mut self,
+        config: SharingConfig,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.allowed = AllowedPeers::parse(&config.peers)?;
+        if self.allowed.is_empty() {
+            log::warn!("No peers are allowed to use the local model");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OnEvent<Incoming<ModelChatService>> for ModelProviderParticle {
+    async fn handle(
+        &mut self,
+        msg: Incoming<ModelChatService>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        let Incoming {
+            peer,
+            request,
+            responder,
+        } = msg;
+        if let Err(err) = self.allowed.check(&peer) {
+            log::warn!("A model request is rejected: {err}");
+            responder.send(Err(err));
+            return Ok(());
+        }
+        // Only local models are shared to avoid forwarding loops
+        match self.substance.router.get_local_model().await {
+            Ok(model) => {
+                crb::core::spawn(async move {
+                    let response = chat(model, request).await;
+                    responder.send(response);
+                });
+            }
+            Err(err) => {
+                responder.send(Err(err));
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn chat(model: ModelLink, request: RemoteRequest) -> Result<RemoteResponse> {
+    let response = model.chat(request.into()).await?;
+    Ok(response.into())
+}
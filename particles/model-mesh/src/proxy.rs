@@ -0,0 +1,171 @@
+use crate::flow::{ModelChatService, ModelService};
+use anyhow::Result;
+use async_trait::async_trait;
+use crb::agent::{Agent, Context, DoAsync, Next, OnEvent};
+use crb::superagent::{Fetcher, Interplay, StreamSession, Supervisor, SupervisorSession};
+use n9_core::{
+    ModelAddress, ModelId, ModelLink, ModelMeta, Particle, SubstanceLinks, ToolingChatRequest,
+    ToolingChatResponse,
+};
+use std::time::Duration;
+use ui9_dui::{Sub, SubEvent};
+use ui9_net::service;
+use ui9_net::tracers::peer::{Peer, PeerChange, PeerId, PeerSet};
+use ui9_net::RemoteUnifiedExt;
+
+/// Remote models could generate long responses.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Adds models shared by peers of the mesh to the local router.
+pub struct ModelMeshParticle {
+    substance: SubstanceLinks,
+    peers: Sub<Peer>,
+    known: PeerSet,
+}
+
+impl Particle for ModelMeshParticle {
+    fn construct(substance: SubstanceLinks) -> Self {
+        Self {
+            substance,
+            peers: Sub::local_unified(),
+            known: PeerSet::default(),
+        }
+    }
+}
+
+impl Supervisor for ModelMeshParticle {
+    type BasedOn = StreamSession<Self>;
+    type GroupBy = PeerId;
+}
+
+impl Agent for ModelMeshParticle {
+    type Context = SupervisorSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+}
+
+struct Initialize;
+
+#[async_trait]
+impl DoAsync<Initialize> for ModelMeshParticle {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        ctx.consume(self.peers.events()?);
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl OnEvent<SubEvent<Peer>> for ModelMeshParticle {
+    async fn handle(&mut self, event: SubEvent<Peer>, ctx: &mut Context<Self>) -> Result<()> {
+        for change in self.known.update(event) {
+            match change {
+                PeerChange::Added(peer) => {
+                    let agent = PeerModel::new(peer, self.substance.clone());
+                    ctx.spawn_agent(agent, peer);
+                }
+                PeerChange::Removed(peer) => {
+                    ctx.tracker.terminate_group(peer);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A proxy to the model of a remote peer.
+struct PeerModel {
+    peer: PeerId,
+    substance: SubstanceLinks,
+    service: Sub<ModelService>,
+    model_id: Option<ModelId>,
+}
+
+impl PeerModel {
+    fn new(peer: PeerId, substance: SubstanceLinks) -> Self {
+        Self {
+            peer,
+            substance,
+            service: Sub::remote_unified(peer),
+            model_id: None,
+        }
+    }
+
+    fn detach(&mut self) {
+        if let Some(id) = self.model_id.take() {
+            self.substance.router.remove_model(id).ok();
+        }
+    }
+}
+
+impl Agent for PeerModel {
+    type Context = StreamSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+
+    fn end(&mut self) {
+        self.detach();
+    }
+}
+
+#[async_trait]
+impl DoAsync<Initialize> for PeerModel {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        ctx.consume(self.service.events()?);
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl OnEvent<SubEvent<ModelService>> for PeerModel {
+    async fn handle(
+        &mut self,
+        event: SubEvent<ModelService>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        match event {
+            SubEvent::State(_) => {
+                // The state is received only if the peer shares a model
+                if self.model_id.is_none() {
+                    let address = RemoteModelAddress { peer: self.peer };
+                    let peer = self.peer.to_string();
+                    let meta = ModelMeta {
+                        name: format!("mesh_{}", &peer[peer.len().saturating_sub(8)..]),
+                        remote: Some(peer),
+                    };
+                    let link = ModelLink::new(address);
+                    let id = self.substance.router.add_model_link(link, meta).await?;
+                    self.model_id = Some(id);
+                }
+            }
+            SubEvent::Event(()) => {}
+            SubEvent::Lost => {
+                self.detach();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends every request by its own stream, so pending requests
+/// are failed by the timeout if the peer is gone.
+struct RemoteModelAddress {
+    peer: PeerId,
+}
+
+impl ModelAddress for RemoteModelAddress {
+    fn chat(&self, request: ToolingChatRequest) -> Fetcher<ToolingChatResponse> {
+        let (interplay, fetcher) = Interplay::new_pair(request);
+        let peer = self.peer;
+        crb::core::spawn(async move {
+            let request = interplay.request.into();
+            let response = service::call::<ModelChatService>(peer, request, RESPONSE_TIMEOUT).await;
+            let response = response.map(ToolingChatResponse::from);
+            interplay.responder.send_result(response).ok();
+        });
+        fetcher
+    }
+}
@@ -0,0 +1,169 @@
+use crate::config::{ModelOptions, OllamaConfig};
+use crate::convert::{self, OllamaMessage, OllamaRole, OllamaTool};
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use n9_core::{ToolingChatRequest, ToolingChatResponse};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The server has to answer the probe quickly, even if requests take long.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A client of the Ollama chat API.
+/// It's cheap to clone and is reused by requests.
+#[derive(Clone)]
+pub struct OllamaClient {
+    http: reqwest::Client,
+    host: String,
+    model: String,
+    stream: bool,
+    options: ModelOptions,
+}
+
+impl OllamaClient {
+    pub fn new(config: &OllamaConfig) -> Result<Self> {
+        let mut http = reqwest::Client::builder();
+        if let Some(secs) = config.timeout {
+            let timeout = Duration::from_secs(secs);
+            // Streamed responses could take longer than the timeout
+            http = if config.stream {
+                http.read_timeout(timeout)
+            } else {
+                http.timeout(timeout)
+            };
+        }
+        Ok(Self {
+            http: http.build()?,
+            host: config.host.trim_end_matches('/').to_string(),
+            model: config.model.clone(),
+            stream: config.stream,
+            options: config.options.clone(),
+        })
+    }
+
+    /// Checks the server is reachable and returns its version.
+    pub async fn version(&self) -> Result<String> {
+        let url = format!("{}/api/version", self.host);
+        let response = self
+            .http
+            .get(&url)
+            .timeout(PROBE_TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?;
+        let version: VersionResponse = response.json().await?;
+        Ok(version.version)
+    }
+
+    pub async fn chat(&self, request: ToolingChatRequest) -> Result<ToolingChatResponse> {
+        let tools = request.tools.iter().map(convert::tool).collect();
+        let messages = convert::messages(request.messages);
+        let message = self.send(messages, tools).await?;
+        let messages = vec![convert::choice(message)];
+        Ok(ToolingChatResponse { messages })
+    }
+
+    async fn send(
+        &self,
+        messages: Vec<OllamaMessage>,
+        tools: Vec<OllamaTool>,
+    ) -> Result<OllamaMessage> {
+        let request = ChatRequest {
+            model: &self.model,
+            messages,
+            tools,
+            stream: self.stream,
+            options: &self.options,
+        };
+        let url = format!("{}/api/chat", self.host);
+        let response = self.http.post(&url).json(&request).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error = response
+                .json::<ChatChunk>()
+                .await
+                .ok()
+                .and_then(|chunk| chunk.error)
+                .unwrap_or_else(|| "Unknown error".into());
+            return Err(anyhow!("Ollama error {status}: {error}"));
+        }
+        if !self.stream {
+            let chunk: ChatChunk = response.json().await?;
+            return chunk.into_message();
+        }
+
+        // Chunks are JSON lines, they are joined into a single message
+        let mut message = OllamaMessage {
+            role: OllamaRole::Assistant,
+            content: String::new(),
+            tool_calls: Vec::new(),
+            tool_name: None,
+        };
+        let mut buffer = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(bytes) = stream.next().await {
+            buffer.extend_from_slice(&bytes?);
+            while let Some(pos) = buffer.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=pos).collect();
+                if merge_chunk(&mut message, &line)? {
+                    return Ok(message);
+                }
+            }
+        }
+        // The last line could be sent without a line break
+        if merge_chunk(&mut message, &buffer)? {
+            return Ok(message);
+        }
+        Err(anyhow!("The response of Ollama is not completed"))
+    }
+}
+
+/// Adds a chunk to the message and tells if the response is done.
+fn merge_chunk(message: &mut OllamaMessage, line: &[u8]) -> Result<bool> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(false);
+    }
+    let chunk: ChatChunk = serde_json::from_slice(line)?;
+    if let Some(error) = chunk.error {
+        return Err(anyhow!("Ollama error: {error}"));
+    }
+    // The last chunk could have no message
+    if let Some(part) = chunk.message {
+        message.content.push_str(&part.content);
+        message.tool_calls.extend(part.tool_calls);
+    }
+    Ok(chunk.done)
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<OllamaMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<OllamaTool>,
+    stream: bool,
+    options: &'a ModelOptions,
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct ChatChunk {
+    message: Option<OllamaMessage>,
+    #[serde(default)]
+    done: bool,
+    error: Option<String>,
+}
+
+impl ChatChunk {
+    fn into_message(self) -> Result<OllamaMessage> {
+        if let Some(error) = self.error {
+            return Err(anyhow!("Ollama error: {error}"));
+        }
+        self.message
+            .ok_or_else(|| anyhow!("The response of Ollama has no message"))
+    }
+}
@@ -0,0 +1,80 @@
+use crate::client::OllamaClient;
+use anyhow::Result;
+use n9_core::{Config, FieldDoc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct OllamaConfig {
+    #[serde(default = "default_host")]
+    pub host: String,
+    pub model: String,
+    /// Receive the response by chunks. It only affects the transport: chunks are
+    /// joined into a single message, but long generations don't hit the timeout.
+    #[serde(default = "enabled")]
+    pub stream: bool,
+    /// Seconds to wait for a response (or for a chunk if it's streamed)
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub options: ModelOptions,
+}
+
+fn default_host() -> String {
+    "http://localhost:11434".into()
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// Options of the model, not set values are taken from the modelfile.
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct ModelOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_predict: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+}
+
+impl Config for OllamaConfig {
+    const NAMESPACE: &str = "ollama";
+    const FIELDS: &[FieldDoc] = &[
+        FieldDoc::new("host", "The address of the Ollama server"),
+        FieldDoc::new("model", "The model used for requests, it has to be pulled"),
+        FieldDoc::new(
+            "stream",
+            "Receive responses by chunks to apply the timeout to every chunk, the answer is still delivered at once",
+        ),
+        FieldDoc::new("timeout", "Seconds to wait for a response or a chunk of it"),
+        FieldDoc::new("options.temperature", "The sampling temperature"),
+        FieldDoc::new("options.num_ctx", "The size of the context window"),
+        FieldDoc::new(
+            "options.num_predict",
+            "The maximum number of tokens to generate",
+        ),
+        FieldDoc::new("options.seed", "The seed for deterministic sampling"),
+    ];
+
+    fn template() -> Self {
+        Self {
+            host: default_host(),
+            model: "llama3.2".into(),
+            stream: true,
+            timeout: Some(120),
+            options: ModelOptions {
+                temperature: Some(0.8),
+                ..ModelOptions::default()
+            },
+        }
+    }
+}
+
+impl OllamaConfig {
+    pub fn extract(&self) -> Result<OllamaClient> {
+        OllamaClient::new(self)
+    }
+}
@@ -0,0 +1,123 @@
+use n9_core::{Message as ModelMessage, Role as ModelRole, ToolCall, ToolInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OllamaRole {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OllamaMessage {
+    pub role: OllamaRole,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<OllamaToolCall>,
+    /// The tool that produced the result
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OllamaToolCall {
+    pub function: OllamaFunction,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OllamaFunction {
+    pub name: String,
+    pub arguments: Value,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OllamaTool {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub function: OllamaToolInfo,
+}
+
+#[derive(Serialize, Debug)]
+pub struct OllamaToolInfo {
+    pub name: String,
+    pub description: String,
+    pub parameters: Value,
+}
+
+/// Converts the history. Ollama doesn't identify calls, so results
+/// refer to tools by names taken from the calls they answer.
+pub fn messages(from: Vec<ModelMessage>) -> Vec<OllamaMessage> {
+    let mut tools_of_calls = HashMap::new();
+    let mut messages = Vec::new();
+    for message in from {
+        let role = match message.role {
+            ModelRole::Developer => OllamaRole::System,
+            ModelRole::User => OllamaRole::User,
+            ModelRole::Assistant => OllamaRole::Assistant,
+            ModelRole::Tool => OllamaRole::Tool,
+        };
+        let tool_name = message
+            .tool_call_id
+            .and_then(|id| tools_of_calls.get(&id).cloned());
+        let tool_calls = message
+            .tool_calls
+            .into_iter()
+            .map(|call| {
+                tools_of_calls.insert(call.id, call.tool.clone());
+                let arguments = serde_json::from_str(&call.arguments).unwrap_or_else(|_| json!({}));
+                OllamaToolCall {
+                    function: OllamaFunction {
+                        name: call.tool,
+                        arguments,
+                    },
+                }
+            })
+            .collect();
+        messages.push(OllamaMessage {
+            role,
+            content: message.content,
+            tool_calls,
+            tool_name,
+        });
+    }
+    messages
+}
+
+/// Tools are named by their ids to find them when they are called.
+pub fn tool(from: &ToolInfo) -> OllamaTool {
+    let meta = &from.meta.meta;
+    OllamaTool {
+        kind: "function",
+        function: OllamaToolInfo {
+            name: from.id.clone(),
+            description: meta.description.clone().unwrap_or_default(),
+            parameters: meta
+                .parameters
+                .clone()
+                .unwrap_or_else(|| json!({ "type": "object", "properties": {} })),
+        },
+    }
+}
+
+/// Converts the response, calls get ids by their positions.
+pub fn choice(from: OllamaMessage) -> ModelMessage {
+    let tool_calls = from
+        .tool_calls
+        .into_iter()
+        .enumerate()
+        .map(|(index, call)| ToolCall {
+            id: format!("call_{index}"),
+            tool: call.function.name,
+            arguments: call.function.arguments.to_string(),
+        })
+        .collect();
+    ModelMessage {
+        tool_calls,
+        ..ModelMessage::new(ModelRole::Assistant, from.content)
+    }
+}
@@ -0,0 +1,8 @@
+mod client;
+mod config;
+mod convert;
+mod particle;
+
+pub use client::OllamaClient;
+pub use config::OllamaConfig;
+pub use particle::OllamaParticle;
@@ -0,0 +1,111 @@
+use crate::client::OllamaClient;
+use crate::config::OllamaConfig;
+use anyhow::Result;
+use async_trait::async_trait;
+use crb::agent::{Agent, AgentSession, Context, DoAsync, Next};
+use crb::core::Slot;
+use crb::superagent::{Entry, OnRequest};
+use n9_core::{
+    ConfigSegmentUpdates, HealthCell, HealthStatus, Model, Particle, SubstanceBond, SubstanceLinks,
+    ToolingChatRequest, ToolingChatResponse, UpdateConfig,
+};
+use ui9_dui::Operation;
+
+const NOT_CONFIGURED: &str = "The client is not configured";
+
+pub struct OllamaParticle {
+    substance: SubstanceLinks,
+    config_updates: Option<Entry<ConfigSegmentUpdates>>,
+    bond: Slot<SubstanceBond<Self>>,
+    client: Slot<OllamaClient>,
+    health: HealthCell,
+}
+
+impl Model for OllamaParticle {}
+
+impl Particle for OllamaParticle {
+    fn construct(substance: SubstanceLinks) -> Self {
+        Self {
+            substance,
+            config_updates: None,
+            bond: Slot::empty(),
+            client: Slot::empty(),
+            health: HealthCell::new(HealthStatus::unhealthy(NOT_CONFIGURED)),
+        }
+    }
+}
+
+impl Agent for OllamaParticle {
+    type Context = AgentSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+}
+
+struct Initialize;
+
+#[async_trait]
+impl DoAsync<Initialize> for OllamaParticle {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        let mut bond = self.substance.bond(&ctx);
+        bond.add_health(&self.health)?;
+
+        let (config, entry) = bond.live_config_updates().await?;
+        self.config_updates = Some(entry);
+        self.update_config(config, ctx).await?;
+
+        bond.add_model()?;
+        self.bond.fill(bond)?;
+
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl UpdateConfig<OllamaConfig> for OllamaParticle {
+    async fn update_config(
+        &mut self,
+        config: OllamaConfig,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        if self.client.is_filled() {
+            self.health.set(HealthStatus::unhealthy(NOT_CONFIGURED));
+            self.client.take()?;
+        }
+        let client = config.extract()?;
+        // The model is healthy only when the server is reachable
+        let status = match client.version().await {
+            Ok(_) => HealthStatus::Healthy,
+            Err(err) => HealthStatus::unhealthy(format!("Ollama is not reachable: {err}")),
+        };
+        self.client.fill(client)?;
+        self.health.set(status);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OnRequest<ToolingChatRequest> for OllamaParticle {
+    async fn on_request(
+        &mut self,
+        request: ToolingChatRequest,
+        _: &mut Context<Self>,
+    ) -> Result<ToolingChatResponse> {
+        let mut op = Operation::start("Sending a request to Ollama");
+        let client = self.client.get_mut()?;
+        let result = client.chat(request).await;
+        match &result {
+            Ok(_) => {
+                self.health.set(HealthStatus::Healthy);
+                op.end("A request to Ollama completed");
+            }
+            Err(err) => {
+                self.health.set(HealthStatus::unhealthy(err));
+                op.failure(&format!("A request to Ollama failed: {err}"));
+                op.end("A request to Ollama is not completed");
+            }
+        }
+        result
+    }
+}
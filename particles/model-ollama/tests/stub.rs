@@ -0,0 +1,176 @@
+use n9_core::{
+    Config, Message, Role, ToolCall, ToolInfo, ToolMeta, ToolMetaWithId, ToolingChatRequest,
+};
+use n9_model_ollama::{OllamaClient, OllamaConfig};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Serves a single request with the response and returns the body of the request.
+async fn stub_server(status: &'static str, response: String) -> (String, JoinHandle<Value>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).await.unwrap();
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).await.unwrap();
+        let reply = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: application/x-ndjson\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+            response.len()
+        );
+        reader.get_mut().write_all(reply.as_bytes()).await.unwrap();
+        reader.get_mut().shutdown().await.unwrap();
+        if body.is_empty() {
+            return Value::Null;
+        }
+        serde_json::from_slice(&body).unwrap()
+    });
+    (host, handle)
+}
+
+fn client(host: String, stream: bool) -> OllamaClient {
+    let config = OllamaConfig {
+        host,
+        stream,
+        ..OllamaConfig::template()
+    };
+    config.extract().unwrap()
+}
+
+fn price_tool() -> ToolInfo {
+    let meta = ToolMeta {
+        name: "price".into(),
+        description: Some("The price of the market".into()),
+        parameters: Some(json!({
+            "type": "object",
+            "properties": { "market": { "type": "string" } },
+        })),
+    };
+    let meta = ToolMetaWithId {
+        id: "price_0".into(),
+        meta,
+    };
+    ToolInfo {
+        meta: Arc::new(meta),
+    }
+}
+
+#[tokio::test]
+async fn test_streamed_response() {
+    let chunks = [
+        json!({ "message": { "role": "assistant", "content": "Hello" }, "done": false }),
+        json!({ "message": { "role": "assistant", "content": ", world" }, "done": false }),
+        json!({ "message": { "role": "assistant", "content": "" }, "done": true }),
+    ];
+    let response: String = chunks.iter().map(|chunk| format!("{chunk}\n")).collect();
+    let (host, server) = stub_server("200 OK", response).await;
+
+    let request = ToolingChatRequest {
+        messages: vec![
+            Message::new(Role::Developer, "Be brief"),
+            Message::new(Role::User, "Hi"),
+        ],
+        tools: Vec::new(),
+    };
+    let response = client(host, true).chat(request).await.unwrap();
+    assert_eq!(response.squash(), "Hello, world");
+
+    let body = server.await.unwrap();
+    assert_eq!(body["model"], "llama3.2");
+    assert_eq!(body["stream"], true);
+    assert_eq!(body["messages"][0]["role"], "system");
+    assert_eq!(body["messages"][1]["content"], "Hi");
+    assert!(body.get("tools").is_none());
+}
+
+#[tokio::test]
+async fn test_parallel_tool_calls() {
+    let call = |market: &str| json!({ "function": { "name": "price_0", "arguments": { "market": market } } });
+    let response = json!({
+        "message": {
+            "role": "assistant",
+            "content": "",
+            "tool_calls": [call("BTC-USD"), call("ETH-USD")],
+        },
+        "done": true,
+    });
+    let (host, server) = stub_server("200 OK", response.to_string()).await;
+
+    let previous = ToolCall {
+        id: "call_0".into(),
+        tool: "price_0".into(),
+        arguments: "{\"market\":\"SOL-USD\"}".into(),
+    };
+    let request = ToolingChatRequest {
+        messages: vec![
+            Message::new(Role::User, "Prices?"),
+            Message {
+                tool_calls: vec![previous],
+                ..Message::new(Role::Assistant, "")
+            },
+            Message::tool_result("call_0".into(), "150"),
+        ],
+        tools: vec![price_tool()],
+    };
+    let response = client(host, false).chat(request).await.unwrap();
+    let calls = response.tool_calls();
+    let ids: Vec<_> = calls.iter().map(|call| call.id.as_str()).collect();
+    assert_eq!(ids, ["call_0", "call_1"]);
+    assert_eq!(calls[1].tool, "price_0");
+    assert_eq!(calls[1].arguments, "{\"market\":\"ETH-USD\"}");
+
+    let body = server.await.unwrap();
+    assert_eq!(body["stream"], false);
+    assert_eq!(body["tools"][0]["function"]["name"], "price_0");
+    let history = &body["messages"];
+    assert_eq!(
+        history[1]["tool_calls"][0]["function"]["arguments"]["market"],
+        "SOL-USD"
+    );
+    assert_eq!(history[2]["role"], "tool");
+    assert_eq!(history[2]["tool_name"], "price_0");
+}
+
+#[tokio::test]
+async fn test_version_probe() {
+    let response = json!({ "version": "0.6.2" }).to_string();
+    let (host, _server) = stub_server("200 OK", response).await;
+    let version = client(host, false).version().await.unwrap();
+    assert_eq!(version, "0.6.2");
+
+    // Nothing listens on the port anymore
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = format!("http://{}", listener.local_addr().unwrap());
+    drop(listener);
+    assert!(client(host, false).version().await.is_err());
+}
+
+#[tokio::test]
+async fn test_error_response() {
+    let response = json!({ "error": "model \"llama3.2\" not found" }).to_string();
+    let (host, _server) = stub_server("404 Not Found", response).await;
+
+    let request = ToolingChatRequest {
+        messages: vec![Message::new(Role::User, "Hi")],
+        tools: Vec::new(),
+    };
+    let err = client(host, true).chat(request).await.unwrap_err();
+    assert!(err.to_string().contains("not found"), "{err}");
+}
@@ -1,26 +1,245 @@
-use async_openai::{config::OpenAIConfig as RawConfig, Client as OpenAIClient};
-use n9_core::Config;
+use anyhow::Result;
+use async_openai::config::{AzureConfig, OpenAIConfig as RawConfig, OPENAI_API_BASE};
+use async_openai::error::OpenAIError;
+use async_openai::types::{
+    CreateChatCompletionRequest, CreateChatCompletionRequestArgs, CreateChatCompletionResponse,
+    Stop,
+};
+use async_openai::Client as OpenAIClient;
+use n9_core::{Config, FieldDoc};
 use serde::{Deserialize, Serialize};
-
-pub type Client = OpenAIClient<RawConfig>;
+use std::time::Duration;
 
 #[derive(Deserialize, Serialize)]
 pub struct OpenAIConfig {
-    api_key: String,
+    pub api_key: String,
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// The endpoint of the API, OpenAI is used if it's not set
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub organization: Option<String>,
+    /// Turns on Azure-style requests: the `base_url` is the resource
+    /// and the `model` is the deployment.
+    #[serde(default)]
+    pub api_version: Option<String>,
+    /// Seconds to wait for a response
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    #[serde(default = "enabled")]
+    pub ping: bool,
+    #[serde(default)]
+    pub params: GenerationParams,
+}
+
+fn default_model() -> String {
+    "gpt-4o".into()
+}
+
+fn enabled() -> bool {
+    true
+}
+
+/// Parameters of generation sent with every request.
+/// Not set values are decided by the server.
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct GenerationParams {
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    /// Sends the limit as `max_tokens` instead of `max_completion_tokens`.
+    /// It's used by default for custom endpoints and Azure, since many of them
+    /// don't support the new field.
+    #[serde(default)]
+    pub legacy_max_tokens: Option<bool>,
+    #[serde(default)]
+    pub presence_penalty: Option<f32>,
+    #[serde(default)]
+    pub frequency_penalty: Option<f32>,
+    #[serde(default)]
+    pub seed: Option<i64>,
+    #[serde(default)]
+    pub stop: Vec<String>,
 }
 
 impl Config for OpenAIConfig {
     const NAMESPACE: &str = "openai";
+    const SECRETS: &[&str] = &["api_key"];
+    const FIELDS: &[FieldDoc] = &[
+        FieldDoc::new(
+            "api_key",
+            "The OpenAI API key, could be a reference like { env = \"OPENAI_API_KEY\" }",
+        ),
+        FieldDoc::new(
+            "model",
+            "The model (or the Azure deployment) used for requests",
+        ),
+        FieldDoc::new(
+            "base_url",
+            "The endpoint of an OpenAI-compatible API, e.g. http://localhost:8080/v1 for llama.cpp",
+        ),
+        FieldDoc::new("organization", "The OpenAI organization of requests"),
+        FieldDoc::new(
+            "api_version",
+            "The Azure API version, turns on Azure-style requests to the base_url",
+        ),
+        FieldDoc::new("timeout", "Seconds to wait for a response"),
+        FieldDoc::new(
+            "ping",
+            "Check the API by listing models on start, not all compatible servers support it",
+        ),
+        FieldDoc::new("params.temperature", "The sampling temperature"),
+        FieldDoc::new("params.top_p", "The nucleus sampling probability mass"),
+        FieldDoc::new(
+            "params.max_tokens",
+            "The maximum number of tokens to generate",
+        ),
+        FieldDoc::new(
+            "params.legacy_max_tokens",
+            "Send max_tokens instead of max_completion_tokens, by default for custom endpoints and Azure",
+        ),
+        FieldDoc::new("params.presence_penalty", "The penalty for new topics"),
+        FieldDoc::new(
+            "params.frequency_penalty",
+            "The penalty for repeated tokens",
+        ),
+        FieldDoc::new("params.seed", "The seed for deterministic sampling"),
+        FieldDoc::new("params.stop", "Sequences that stop the generation"),
+    ];
 
     fn template() -> Self {
         Self {
             api_key: "API KEY HERE".into(),
+            model: default_model(),
+            base_url: Some(OPENAI_API_BASE.into()),
+            organization: None,
+            api_version: None,
+            timeout: Some(120),
+            ping: true,
+            params: GenerationParams {
+                temperature: Some(1.0),
+                max_tokens: Some(1024),
+                ..GenerationParams::default()
+            },
         }
     }
 }
 
 impl OpenAIConfig {
-    pub fn extract(&self) -> RawConfig {
-        RawConfig::default().with_api_key(&self.api_key)
+    pub fn extract(&self) -> Result<Client> {
+        let mut http = reqwest::Client::builder();
+        if let Some(secs) = self.timeout {
+            http = http.timeout(Duration::from_secs(secs));
+        }
+        let http = http.build()?;
+        let client = if let Some(version) = &self.api_version {
+            let mut config = AzureConfig::new()
+                .with_api_key(&self.api_key)
+                .with_api_version(version)
+                .with_deployment_id(&self.model);
+            if let Some(base_url) = &self.base_url {
+                config = config.with_api_base(base_url);
+            }
+            Client::Azure(OpenAIClient::with_config(config).with_http_client(http))
+        } else {
+            let mut config = RawConfig::new().with_api_key(&self.api_key);
+            if let Some(base_url) = &self.base_url {
+                config = config.with_api_base(base_url);
+            }
+            if let Some(organization) = &self.organization {
+                config = config.with_org_id(organization);
+            }
+            Client::OpenAI(OpenAIClient::with_config(config).with_http_client(http))
+        };
+        Ok(client)
+    }
+
+    /// A request with the model and parameters of the config.
+    pub fn request(&self) -> CreateChatCompletionRequestArgs {
+        let params = &self.params;
+        let mut request = CreateChatCompletionRequestArgs::default();
+        request.model(&self.model);
+        if let Some(temperature) = params.temperature {
+            request.temperature(temperature);
+        }
+        if let Some(top_p) = params.top_p {
+            request.top_p(top_p);
+        }
+        if let Some(max_tokens) = params.max_tokens {
+            if self.legacy_max_tokens() {
+                #[allow(deprecated)]
+                request.max_tokens(max_tokens);
+            } else {
+                request.max_completion_tokens(max_tokens);
+            }
+        }
+        if let Some(penalty) = params.presence_penalty {
+            request.presence_penalty(penalty);
+        }
+        if let Some(penalty) = params.frequency_penalty {
+            request.frequency_penalty(penalty);
+        }
+        if let Some(seed) = params.seed {
+            request.seed(seed);
+        }
+        if !params.stop.is_empty() {
+            request.stop(Stop::StringArray(params.stop.clone()));
+        }
+        request
+    }
+
+    fn legacy_max_tokens(&self) -> bool {
+        self.params.legacy_max_tokens.unwrap_or_else(|| {
+            let custom_url = self
+                .base_url
+                .as_ref()
+                .is_some_and(|url| url.trim_end_matches('/') != OPENAI_API_BASE);
+            custom_url || self.api_version.is_some()
+        })
+    }
+}
+
+/// A client of OpenAI or an Azure-style endpoint.
+pub enum Client {
+    OpenAI(OpenAIClient<RawConfig>),
+    Azure(OpenAIClient<AzureConfig>),
+}
+
+impl Client {
+    pub async fn ping(&self) -> Result<(), OpenAIError> {
+        match self {
+            Self::OpenAI(client) => client.models().list().await?,
+            Self::Azure(client) => client.models().list().await?,
+        };
+        Ok(())
+    }
+
+    pub async fn chat(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse, OpenAIError> {
+        match self {
+            Self::OpenAI(client) => client.chat().create(request).await,
+            Self::Azure(client) => client.chat().create(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_max_tokens() {
+        let mut config = OpenAIConfig::template();
+        assert!(!config.legacy_max_tokens());
+        config.base_url = Some("http://localhost:8080/v1".into());
+        assert!(config.legacy_max_tokens());
+        config.params.legacy_max_tokens = Some(false);
+        assert!(!config.legacy_max_tokens());
     }
 }
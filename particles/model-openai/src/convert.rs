@@ -1,5 +1,5 @@
 use async_openai::types::*;
-use n9_core::{Message as ModelMessage, Role as ModelRole};
+use n9_core::{Message as ModelMessage, Role as ModelRole, ToolCall, ToolInfo};
 
 pub fn message(from: ModelMessage) -> ChatCompletionRequestMessage {
     match from.role {
@@ -17,10 +17,49 @@ pub fn message(from: ModelMessage) -> ChatCompletionRequestMessage {
         }
         ModelRole::Assistant => {
             let mut message = ChatCompletionRequestAssistantMessage::default();
-            let content = ChatCompletionRequestAssistantMessageContent::Text(from.content);
-            message.content = Some(content);
+            // Content could be empty if the assistant only calls tools
+            if !from.content.is_empty() || from.tool_calls.is_empty() {
+                let content = ChatCompletionRequestAssistantMessageContent::Text(from.content);
+                message.content = Some(content);
+            }
+            if !from.tool_calls.is_empty() {
+                let tool_calls = from.tool_calls.into_iter().map(tool_call).collect();
+                message.tool_calls = Some(tool_calls);
+            }
             ChatCompletionRequestMessage::from(message)
         }
+        ModelRole::Tool => {
+            let content = ChatCompletionRequestToolMessageContent::Text(from.content);
+            let message = ChatCompletionRequestToolMessage {
+                content,
+                tool_call_id: from.tool_call_id.unwrap_or_default(),
+            };
+            ChatCompletionRequestMessage::from(message)
+        }
+    }
+}
+
+fn tool_call(from: ToolCall) -> ChatCompletionMessageToolCall {
+    ChatCompletionMessageToolCall {
+        id: from.id,
+        r#type: ChatCompletionToolType::Function,
+        function: FunctionCall {
+            name: from.tool,
+            arguments: from.arguments,
+        },
+    }
+}
+
+/// Tools are named by their ids to find them when they are called.
+pub fn tool(from: &ToolInfo) -> ChatCompletionTool {
+    ChatCompletionTool {
+        r#type: ChatCompletionToolType::Function,
+        function: FunctionObject {
+            name: from.id.clone(),
+            description: from.meta.meta.description.clone(),
+            parameters: from.meta.meta.parameters.clone(),
+            strict: None,
+        },
     }
 }
 
@@ -33,7 +72,90 @@ pub fn choice(from: ChatChoice) -> Option<ModelMessage> {
             return None;
         }
     };
-    let content = from.message.content?;
-    let message = ModelMessage { role, content };
+    let tool_calls: Vec<_> = from
+        .message
+        .tool_calls
+        .unwrap_or_default()
+        .into_iter()
+        .map(|call| ToolCall {
+            id: call.id,
+            tool: call.function.name,
+            arguments: call.function.arguments,
+        })
+        .collect();
+    // Messages with tool calls could have no content
+    if from.message.content.is_none() && tool_calls.is_empty() {
+        return None;
+    }
+    let content = from.message.content.unwrap_or_default();
+    let message = ModelMessage {
+        tool_calls,
+        ..ModelMessage::new(role, content)
+    };
     Some(message)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n9_core::{Tool, ToolMeta, ToolMetaWithId};
+    use n9_exchange_dydx::{DyDxParticle, Price};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parallel_tool_calls() {
+        let choice: ChatChoice = serde_json::from_value(json!({
+            "index": 0,
+            "finish_reason": "tool_calls",
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [
+                    {
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "price_0", "arguments": "{\"market\":\"BTC-USD\"}" }
+                    },
+                    {
+                        "id": "call_2",
+                        "type": "function",
+                        "function": { "name": "price_0", "arguments": "{\"market\":\"ETH-USD\"}" }
+                    }
+                ]
+            }
+        }))
+        .unwrap();
+        let message = super::choice(choice).unwrap();
+        assert!(message.content.is_empty());
+        let ids: Vec<_> = message.tool_calls.iter().map(|call| &call.id).collect();
+        assert_eq!(ids, ["call_1", "call_2"]);
+        assert_eq!(message.tool_calls[1].tool, "price_0");
+
+        // Calls are sent back with the history
+        let ChatCompletionRequestMessage::Assistant(request) = super::message(message) else {
+            panic!("Not an assistant message");
+        };
+        assert!(request.content.is_none());
+        assert_eq!(request.tool_calls.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_tool_parameters() {
+        let meta = ToolMeta {
+            name: "dydx_price".into(),
+            description: None,
+            parameters: <DyDxParticle as Tool<Price>>::parameters(),
+        };
+        let info = ToolInfo {
+            meta: Arc::new(ToolMetaWithId {
+                id: "dydx_price_0".into(),
+                meta,
+            }),
+        };
+        let request = serde_json::to_value(super::tool(&info)).unwrap();
+        let parameters = &request["function"]["parameters"];
+        assert_eq!(parameters["properties"]["ticker"]["type"], "string");
+        assert_eq!(parameters["required"], json!(["ticker"]));
+    }
+}
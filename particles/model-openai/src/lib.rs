@@ -2,4 +2,5 @@ mod config;
 mod convert;
 mod particle;
 
+pub use config::OpenAIConfig;
 pub use particle::OpenAIParticle;
@@ -1,22 +1,25 @@
 use crate::config::{Client, OpenAIConfig};
 use crate::convert;
 use anyhow::Result;
-use async_openai::types::CreateChatCompletionRequestArgs;
 use async_trait::async_trait;
 use crb::agent::{Agent, AgentSession, Context, DoAsync, Next};
 use crb::core::Slot;
 use crb::superagent::{Entry, OnRequest};
 use n9_core::{
-    ConfigSegmentUpdates, Model, Particle, SubstanceBond, SubstanceLinks, ToolingChatRequest,
-    ToolingChatResponse, UpdateConfig,
+    ConfigSegmentUpdates, HealthCell, HealthStatus, Model, Particle, SubstanceBond, SubstanceLinks,
+    ToolingChatRequest, ToolingChatResponse, UpdateConfig,
 };
 use ui9_dui::Operation;
 
+const NOT_CONFIGURED: &str = "The client is not configured";
+
 pub struct OpenAIParticle {
     substance: SubstanceLinks,
     config_updates: Option<Entry<ConfigSegmentUpdates>>,
     bond: Slot<SubstanceBond<Self>>,
     client: Slot<Client>,
+    config: Slot<OpenAIConfig>,
+    health: HealthCell,
 }
 
 impl Model for OpenAIParticle {}
@@ -28,6 +31,8 @@ impl Particle for OpenAIParticle {
             config_updates: None,
             bond: Slot::empty(),
             client: Slot::empty(),
+            config: Slot::empty(),
+            health: HealthCell::new(HealthStatus::unhealthy(NOT_CONFIGURED)),
         }
     }
 }
@@ -46,6 +51,7 @@ struct Initialize;
 impl DoAsync<Initialize> for OpenAIParticle {
     async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
         let mut bond = self.substance.bond(&ctx);
+        bond.add_health(&self.health)?;
 
         let (config, entry) = bond.live_config_updates().await?;
         self.config_updates = Some(entry);
@@ -66,13 +72,20 @@ impl UpdateConfig<OpenAIConfig> for OpenAIParticle {
         _ctx: &mut Context<Self>,
     ) -> Result<()> {
         if self.client.is_filled() {
+            self.health.set(HealthStatus::unhealthy(NOT_CONFIGURED));
             self.client.take()?;
+            self.config.take()?;
         }
 
         let op = Operation::start("Configuring OpenAI");
-        let client = Client::with_config(config.extract());
-        let _models = client.models().list().await?; // An alternative to ping
+        let client = config.extract()?;
+        if config.ping {
+            // Listing models is an alternative to ping
+            client.ping().await?;
+        }
         self.client.fill(client)?;
+        self.config.fill(config)?;
+        self.health.set(HealthStatus::Healthy);
         op.end("OpenAI configured");
         Ok(())
     }
@@ -85,15 +98,32 @@ impl OnRequest<ToolingChatRequest> for OpenAIParticle {
         request: ToolingChatRequest,
         _: &mut Context<Self>,
     ) -> Result<ToolingChatResponse> {
+        let result = self.chat(request).await;
+        let status = match &result {
+            Ok(_) => HealthStatus::Healthy,
+            Err(err) => HealthStatus::unhealthy(err),
+        };
+        self.health.set(status);
+        result
+    }
+}
+
+impl OpenAIParticle {
+    async fn chat(&mut self, request: ToolingChatRequest) -> Result<ToolingChatResponse> {
         let op = Operation::start("Sending a request to OpenAI");
         let client = self.client.get_mut()?;
+        let config = self.config.get_mut()?;
         // TODO: Sequental, but could be executed in the reactor
         let messages: Vec<_> = request.messages.into_iter().map(convert::message).collect();
-        let request = CreateChatCompletionRequestArgs::default()
-            .model("gpt-4o")
-            .messages(messages)
-            .build()?;
-        let response = client.chat().create(request).await?;
+        let mut args = config.request();
+        args.messages(messages);
+        if !request.tools.is_empty() {
+            let tools: Vec<_> = request.tools.iter().map(convert::tool).collect();
+            // Several tools could be called in a single response
+            args.tools(tools).parallel_tool_calls(true);
+        }
+        let request = args.build()?;
+        let response = client.chat(request).await?;
         let messages = response
             .choices
             .into_iter()
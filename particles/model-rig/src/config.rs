@@ -0,0 +1,70 @@
+use crate::proxy::VCompletionModel;
+use n9_core::{Config, FieldDoc};
+use rig::providers::{anthropic, cohere, gemini, openai};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RigProvider {
+    #[serde(rename = "openai")]
+    OpenAI,
+    Anthropic,
+    Gemini,
+    Cohere,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct RigConfig {
+    pub provider: RigProvider,
+    pub api_key: String,
+    pub model: String,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// Required by Anthropic for models unknown to Rig
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+}
+
+impl Config for RigConfig {
+    const NAMESPACE: &str = "rig";
+    const SECRETS: &[&str] = &["api_key"];
+    const FIELDS: &[FieldDoc] = &[
+        FieldDoc::new(
+            "provider",
+            "The provider of the model: openai, anthropic, gemini or cohere",
+        ),
+        FieldDoc::new(
+            "api_key",
+            "The API key of the provider, could be a reference like { env = \"OPENAI_API_KEY\" }",
+        ),
+        FieldDoc::new("model", "The model of the provider used for requests"),
+        FieldDoc::new("temperature", "The sampling temperature"),
+        FieldDoc::new("max_tokens", "The maximum number of tokens to generate"),
+    ];
+
+    fn template() -> Self {
+        Self {
+            provider: RigProvider::OpenAI,
+            api_key: "API KEY HERE".into(),
+            model: "gpt-4o".into(),
+            temperature: None,
+            max_tokens: Some(1024),
+        }
+    }
+}
+
+impl RigConfig {
+    pub fn extract(&self) -> Box<dyn VCompletionModel> {
+        let key = self.api_key.as_str();
+        let model = self.model.as_str();
+        match self.provider {
+            RigProvider::OpenAI => Box::new(openai::Client::new(key).completion_model(model)),
+            RigProvider::Anthropic => {
+                let client = anthropic::ClientBuilder::new(key).build();
+                Box::new(client.completion_model(model))
+            }
+            RigProvider::Gemini => Box::new(gemini::Client::new(key).completion_model(model)),
+            RigProvider::Cohere => Box::new(cohere::Client::new(key).completion_model(model)),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::config::RigConfig;
+use crate::proxy::CompletionResponse;
+use anyhow::{anyhow, Result};
+use n9_core::{Message as ModelMessage, Role as ModelRole, ToolCall, ToolInfo, ToolingChatRequest};
+use rig::completion::{CompletionRequest, Message, ToolDefinition};
+use rig::message::{AssistantContent, ToolResultContent, UserContent};
+use rig::OneOrMany;
+use serde_json::{json, Value};
+
+/// Builds a request of the history, the last message is the prompt.
+///
+/// Developer messages become the preamble, results of parallel tool calls
+/// are merged into a single user message.
+pub fn request(from: ToolingChatRequest, config: &RigConfig) -> Result<CompletionRequest> {
+    let mut preamble = Vec::new();
+    let mut history: Vec<Message> = Vec::new();
+    for message in from.messages {
+        let next = match message.role {
+            ModelRole::Developer => {
+                preamble.push(message.content);
+                continue;
+            }
+            ModelRole::User => Message::user(message.content),
+            ModelRole::Assistant => assistant(message)?,
+            ModelRole::Tool => {
+                let id = message.tool_call_id.unwrap_or_default();
+                let result = OneOrMany::one(ToolResultContent::text(message.content));
+                let content = UserContent::tool_result(id, result);
+                if let Some(Message::User { content: results }) = history.last_mut() {
+                    let is_result =
+                        |content: &UserContent| matches!(content, UserContent::ToolResult(_));
+                    if results.iter().all(is_result) {
+                        results.push(content);
+                        continue;
+                    }
+                }
+                Message::User {
+                    content: OneOrMany::one(content),
+                }
+            }
+        };
+        history.push(next);
+    }
+    let prompt = history
+        .pop()
+        .ok_or_else(|| anyhow!("The request has no messages"))?;
+    let tools = from.tools.iter().map(tool).collect();
+    let request = CompletionRequest {
+        prompt,
+        preamble: (!preamble.is_empty()).then(|| preamble.join("\n\n")),
+        chat_history: history,
+        documents: Vec::new(),
+        tools,
+        temperature: config.temperature,
+        max_tokens: config.max_tokens,
+        additional_params: None,
+    };
+    Ok(request)
+}
+
+fn assistant(message: ModelMessage) -> Result<Message> {
+    let mut content = Vec::new();
+    if !message.content.is_empty() {
+        content.push(AssistantContent::text(message.content));
+    }
+    for call in message.tool_calls {
+        let arguments = serde_json::from_str(&call.arguments).unwrap_or_else(|_| json!({}));
+        content.push(AssistantContent::tool_call(call.id, call.tool, arguments));
+    }
+    let content =
+        OneOrMany::many(content).map_err(|_| anyhow!("The assistant message is empty"))?;
+    Ok(Message::Assistant { content })
+}
+
+/// Tools are named by their ids to find them when they are called.
+fn tool(from: &ToolInfo) -> ToolDefinition {
+    let meta = &from.meta.meta;
+    ToolDefinition {
+        name: from.id.clone(),
+        description: meta.description.clone().unwrap_or_default(),
+        parameters: meta
+            .parameters
+            .clone()
+            .unwrap_or_else(|| json!({ "type": "object", "properties": {} })),
+    }
+}
+
+/// Collects the text and tool calls of the response into a message.
+pub fn choice(response: CompletionResponse) -> ModelMessage {
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+    for content in response.choice.into_iter() {
+        match content {
+            AssistantContent::Text(part) => text.push_str(&part.text),
+            AssistantContent::ToolCall(call) => {
+                let arguments = match call.function.arguments {
+                    Value::String(arguments) => arguments,
+                    arguments => arguments.to_string(),
+                };
+                tool_calls.push(ToolCall {
+                    id: call.id,
+                    tool: call.function.name,
+                    arguments,
+                });
+            }
+        }
+    }
+    ModelMessage {
+        tool_calls,
+        ..ModelMessage::new(ModelRole::Assistant, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use n9_core::Config;
+
+    #[test]
+    fn test_history_with_tool_results() {
+        let config = RigConfig::template();
+        let call = ToolCall {
+            id: "call_1".into(),
+            tool: "price_0".into(),
+            arguments: "{}".into(),
+        };
+        let messages = vec![
+            ModelMessage::new(ModelRole::Developer, "Be brief"),
+            ModelMessage::new(ModelRole::User, "Prices?"),
+            ModelMessage {
+                tool_calls: vec![
+                    call.clone(),
+                    ToolCall {
+                        id: "call_2".into(),
+                        ..call
+                    },
+                ],
+                ..ModelMessage::new(ModelRole::Assistant, "")
+            },
+            ModelMessage::tool_result("call_1".into(), "100"),
+            ModelMessage::tool_result("call_2".into(), "200"),
+        ];
+        let request = ToolingChatRequest {
+            messages,
+            tools: Vec::new(),
+        };
+        let request = super::request(request, &config).unwrap();
+        assert_eq!(request.preamble.as_deref(), Some("Be brief"));
+        assert_eq!(request.chat_history.len(), 2);
+        let Message::User { content } = request.prompt else {
+            panic!("The prompt is not a user message");
+        };
+        assert_eq!(content.len(), 2);
+    }
+}
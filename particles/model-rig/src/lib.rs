@@ -1,4 +1,8 @@
+mod config;
+mod convert;
 mod particle;
 mod proxy;
 
+pub use config::{RigConfig, RigProvider};
 pub use particle::RigModelParticle;
+pub use proxy::VCompletionModel;
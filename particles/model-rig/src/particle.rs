@@ -1,17 +1,22 @@
+use crate::config::RigConfig;
+use crate::convert;
 use crate::proxy::VCompletionModel;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use crb::agent::{Agent, AgentSession, Context, DoAsync, Next};
 use crb::core::Slot;
-use crb::superagent::OnRequest;
+use crb::superagent::{Entry, OnRequest};
 use n9_core::{
-    Model, Particle, SubstanceBond, SubstanceLinks, ToolingChatRequest, ToolingChatResponse,
+    ConfigSegmentUpdates, Model, Particle, SubstanceBond, SubstanceLinks, ToolingChatRequest,
+    ToolingChatResponse, UpdateConfig,
 };
 
 pub struct RigModelParticle {
     substance: SubstanceLinks,
+    config_updates: Option<Entry<ConfigSegmentUpdates>>,
     bond: Slot<SubstanceBond<Self>>,
     model: Option<Box<dyn VCompletionModel>>,
+    config: Slot<RigConfig>,
 }
 
 impl Model for RigModelParticle {}
@@ -20,8 +25,10 @@ impl Particle for RigModelParticle {
     fn construct(substance: SubstanceLinks) -> Self {
         Self {
             substance,
+            config_updates: None,
             bond: Slot::empty(),
             model: None,
+            config: Slot::empty(),
         }
     }
 }
@@ -40,12 +47,29 @@ struct Initialize;
 impl DoAsync<Initialize> for RigModelParticle {
     async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
         let mut bond = self.substance.bond(&ctx);
+
+        let (config, entry) = bond.live_config_updates().await?;
+        self.config_updates = Some(entry);
+        self.update_config(config, ctx).await?;
+
         bond.add_model()?;
         self.bond.fill(bond)?;
         Ok(Next::events())
     }
 }
 
+#[async_trait]
+impl UpdateConfig<RigConfig> for RigModelParticle {
+    async fn update_config(&mut self, config: RigConfig, _ctx: &mut Context<Self>) -> Result<()> {
+        if self.config.is_filled() {
+            self.config.take()?;
+        }
+        self.model = Some(config.extract());
+        self.config.fill(config)?;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl OnRequest<ToolingChatRequest> for RigModelParticle {
     async fn on_request(
@@ -53,6 +77,14 @@ impl OnRequest<ToolingChatRequest> for RigModelParticle {
         request: ToolingChatRequest,
         _: &mut Context<Self>,
     ) -> Result<ToolingChatResponse> {
-        Err(anyhow!("Rig models are not implemented yet"))
+        let config = self.config.get_mut()?;
+        let request = convert::request(request, config)?;
+        let model = self
+            .model
+            .as_ref()
+            .ok_or_else(|| anyhow!("The model is not configured"))?;
+        let response = model.completion(request).await?;
+        let messages = vec![convert::choice(response)];
+        Ok(ToolingChatResponse { messages })
     }
 }
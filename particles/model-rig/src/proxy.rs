@@ -3,12 +3,12 @@ use rig::completion::{
     CompletionError, CompletionModel, CompletionRequest,
     CompletionResponse as RigCompletionResponse,
 };
-use rig::providers::openai;
 
 pub type CompletionResponse = RigCompletionResponse<()>;
 
+/// A completion model of any provider without its raw response type.
 #[async_trait]
-pub trait VCompletionModel: Send {
+pub trait VCompletionModel: Send + Sync {
     async fn completion(
         &self,
         request: CompletionRequest,
@@ -16,7 +16,7 @@ pub trait VCompletionModel: Send {
 }
 
 #[async_trait]
-impl VCompletionModel for openai::CompletionModel {
+impl<M: CompletionModel> VCompletionModel for M {
     async fn completion(
         &self,
         request: CompletionRequest,
@@ -1,15 +1,18 @@
 use eframe::{run_native, CreationContext, NativeOptions};
 use egui::ViewportBuilder;
+use std::collections::BTreeMap;
 use std::time::Duration;
 use ui9_dui::subscriber::State;
+use ui9_dui::tracers::tree::{Level, Tree};
 use ui9_maker::protocol::UiEvent;
 use ui9_maker::AppLink;
-use ui9_net::tracers::peer::Peer;
+use ui9_net::tracers::peer::{Peer, PeerId};
 
 pub struct AppGui {
     state_changed: bool,
     link: AppLink,
     peers: Option<State<Peer>>,
+    trees: BTreeMap<PeerId, State<Tree>>,
 }
 
 impl AppGui {
@@ -34,6 +37,7 @@ impl AppGui {
             state_changed: false,
             link,
             peers: None,
+            trees: BTreeMap::new(),
         }
     }
 }
@@ -68,6 +72,14 @@ impl AppGui {
             UiEvent::StateChanged => {
                 self.state_changed = true;
             }
+            UiEvent::SetTree { peer, tree } => {
+                self.trees.insert(peer, tree);
+                self.state_changed = true;
+            }
+            UiEvent::DelTree { peer } => {
+                self.trees.remove(&peer);
+                self.state_changed = true;
+            }
         }
     }
 
@@ -99,6 +111,9 @@ impl AppGui {
                         });
                         */
                     });
+                    if let Some(tree) = self.trees.get(peer_id) {
+                        render_level(ui, &tree.borrow().root, &peer_id.to_string());
+                    }
                 });
                 ui.add_space(4.0);
             }
@@ -106,3 +121,20 @@ impl AppGui {
         Some(())
     }
 }
+
+fn render_level(ui: &mut egui::Ui, level: &Level, path: &str) {
+    for (name, sublevel) in &level.levels {
+        let path = format!("{path}.{name}");
+        let title = match sublevel.tracer_info.as_ref() {
+            Some(info) => format!("{name} ({})", info.class),
+            None => name.clone(),
+        };
+        if sublevel.levels.is_empty() {
+            ui.label(title);
+        } else {
+            egui::CollapsingHeader::new(title)
+                .id_salt(&path)
+                .show(ui, |ui| render_level(ui, sublevel, &path));
+        }
+    }
+}
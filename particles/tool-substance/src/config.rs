@@ -0,0 +1,21 @@
+use n9_core::{Config, FieldDoc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct DelegationConfig {
+    /// Peers allowed to delegate questions, others are rejected
+    #[serde(default)]
+    pub peers: Vec<String>,
+}
+
+impl Config for DelegationConfig {
+    const NAMESPACE: &str = "delegation";
+    const FIELDS: &[FieldDoc] = &[FieldDoc::new(
+        "peers",
+        "Ids of peers allowed to delegate questions to the substance, others are rejected",
+    )];
+
+    fn template() -> Self {
+        Self { peers: Vec::new() }
+    }
+}
@@ -0,0 +1,51 @@
+use derive_more::{Deref, DerefMut, From, Into};
+use serde::{Deserialize, Serialize};
+use ui9::names::Fqn;
+use ui9_dui::{Flow, Listener, Publisher, Subscriber, Tracer, Unified};
+use ui9_net::service::{Service, StreamProtocol};
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct DelegationSub {
+    listener: Listener<Delegation>,
+}
+
+impl Subscriber for Delegation {
+    type Driver = DelegationSub;
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct DelegationPub {
+    tracer: Tracer<Delegation>,
+}
+
+impl Publisher for Delegation {
+    type Driver = DelegationPub;
+}
+
+/// Announces that the substance answers questions of other peers.
+/// Questions and answers are not published, they are sent by the `DelegationService`.
+#[derive(Clone, Serialize, Deserialize, Default, Debug)]
+pub struct Delegation {}
+
+impl Unified for Delegation {
+    fn fqn() -> Fqn {
+        Fqn::root("@delegation")
+    }
+}
+
+impl Flow for Delegation {
+    type Event = ();
+    type Action = ();
+
+    fn apply(&mut self, _event: Self::Event) {}
+}
+
+/// Delegates a question to the substance of a peer and returns the answer.
+pub struct DelegationService;
+
+impl Service for DelegationService {
+    const PROTOCOL: StreamProtocol = StreamProtocol::new("/n9-delegation");
+
+    type Request = String;
+    type Response = String;
+}
@@ -0,0 +1,9 @@
+mod config;
+mod flow;
+mod provider;
+mod tool;
+
+pub use config::DelegationConfig;
+pub use flow::{Delegation, DelegationService};
+pub use provider::SubstanceProviderParticle;
+pub use tool::SubstanceToolParticle;
@@ -0,0 +1,103 @@
+use crate::config::DelegationConfig;
+use crate::flow::{Delegation, DelegationService};
+use anyhow::Result;
+use async_trait::async_trait;
+use crb::agent::{Agent, AgentSession, Context, DoAsync, Next, OnEvent};
+use crb::superagent::Entry;
+use n9_core::router::RouterLink;
+use n9_core::{ChatRequest, ConfigSegmentUpdates, Particle, SubstanceLinks, UpdateConfig};
+use ui9_dui::Pub;
+use ui9_net::service::{self, AllowedPeers, Incoming};
+
+/// Serves the reasoning of the local substance to allowed peers of the mesh.
+pub struct SubstanceProviderParticle {
+    substance: SubstanceLinks,
+    config_updates: Option<Entry<ConfigSegmentUpdates>>,
+    delegation: Option<Pub<Delegation>>,
+    allowed: AllowedPeers,
+}
+
+impl Particle for SubstanceProviderParticle {
+    fn construct(substance: SubstanceLinks) -> Self {
+        Self {
+            substance,
+            config_updates: None,
+            delegation: None,
+            allowed: AllowedPeers::default(),
+        }
+    }
+}
+
+impl Agent for SubstanceProviderParticle {
+    type Context = AgentSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+}
+
+struct Initialize;
+
+#[async_trait]
+impl DoAsync<Initialize> for SubstanceProviderParticle {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        let mut bond = self.substance.bond(&ctx);
+        let (config, entry) = bond.live_config_updates().await?;
+        self.config_updates = Some(entry);
+        self.update_config(config, ctx).await?;
+
+        let requests = service::serve::<DelegationService>().await?;
+        ctx.consume(requests);
+        // Peers find the substance by the announcement
+        self.delegation = Some(Pub::unified());
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl UpdateConfig<DelegationConfig> for SubstanceProviderParticle {
+    async fn update_config(
+        &mut self,
+        config: DelegationConfig,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.allowed = AllowedPeers::parse(&config.peers)?;
+        if self.allowed.is_empty() {
+            log::warn!("No peers are allowed to delegate questions to the substance");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OnEvent<Incoming<DelegationService>> for SubstanceProviderParticle {
+    async fn handle(
+        &mut self,
+        msg: Incoming<DelegationService>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        let Incoming {
+            peer,
+            request,
+            responder,
+        } = msg;
+        if let Err(err) = self.allowed.check(&peer) {
+            log::warn!("A delegated question is rejected: {err}");
+            responder.send(Err(err));
+            return Ok(());
+        }
+        log::info!("Delegated question received from {peer}");
+        let router = self.substance.router.clone();
+        crb::core::spawn(async move {
+            let answer = ask(router, request).await;
+            responder.send(answer);
+        });
+        Ok(())
+    }
+}
+
+async fn ask(router: RouterLink, question: String) -> Result<String> {
+    let session = router.new_session().await?;
+    let response = session.chat(ChatRequest::user(&question)).await?;
+    Ok(response.squash())
+}
@@ -0,0 +1,197 @@
+use crate::flow::{Delegation, DelegationService};
+use anyhow::Result;
+use async_trait::async_trait;
+use crb::agent::{Agent, Context, DoAsync, Next, OnEvent};
+use crb::superagent::{Responder, StreamSession, Supervisor, SupervisorSession};
+use n9_core::{Particle, SubstanceLinks, Tool, ToolId, ToolResponse};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use ui9_dui::{Sub, SubEvent};
+use ui9_net::service;
+use ui9_net::tracers::peer::{Peer, PeerChange, PeerId, PeerSet};
+use ui9_net::RemoteUnifiedExt;
+
+/// The remote substance could use several models and tools to answer.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Registers substances of discovered peers as tools of the local router.
+pub struct SubstanceToolParticle {
+    substance: SubstanceLinks,
+    peers: Sub<Peer>,
+    known: PeerSet,
+}
+
+impl Particle for SubstanceToolParticle {
+    fn construct(substance: SubstanceLinks) -> Self {
+        Self {
+            substance,
+            peers: Sub::local_unified(),
+            known: PeerSet::default(),
+        }
+    }
+}
+
+impl Supervisor for SubstanceToolParticle {
+    type BasedOn = StreamSession<Self>;
+    type GroupBy = PeerId;
+}
+
+impl Agent for SubstanceToolParticle {
+    type Context = SupervisorSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+}
+
+struct Initialize;
+
+#[async_trait]
+impl DoAsync<Initialize> for SubstanceToolParticle {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        ctx.consume(self.peers.events()?);
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl OnEvent<SubEvent<Peer>> for SubstanceToolParticle {
+    async fn handle(&mut self, event: SubEvent<Peer>, ctx: &mut Context<Self>) -> Result<()> {
+        for change in self.known.update(event) {
+            match change {
+                PeerChange::Added(peer) => {
+                    let agent = PeerTool::new(peer, self.substance.clone());
+                    ctx.spawn_agent(agent, peer);
+                }
+                PeerChange::Removed(peer) => {
+                    ctx.tracker.terminate_group(peer);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A tool that forwards questions to the substance of a remote peer.
+struct PeerTool {
+    peer: PeerId,
+    substance: SubstanceLinks,
+    delegation: Sub<Delegation>,
+    tool_id: Option<ToolId>,
+}
+
+impl PeerTool {
+    fn new(peer: PeerId, substance: SubstanceLinks) -> Self {
+        Self {
+            peer,
+            substance,
+            delegation: Sub::remote_unified(peer),
+            tool_id: None,
+        }
+    }
+
+    fn detach(&mut self) {
+        if let Some(id) = self.tool_id.take() {
+            self.substance.router.remove_tool(id).ok();
+            log::info!("The substance of {} is not available anymore", self.peer);
+        }
+    }
+}
+
+impl Agent for PeerTool {
+    type Context = StreamSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+
+    fn end(&mut self) {
+        self.detach();
+    }
+}
+
+#[async_trait]
+impl DoAsync<Initialize> for PeerTool {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        ctx.consume(self.delegation.events()?);
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl OnEvent<SubEvent<Delegation>> for PeerTool {
+    async fn handle(&mut self, event: SubEvent<Delegation>, ctx: &mut Context<Self>) -> Result<()> {
+        match event {
+            SubEvent::State(_) => {
+                // The state is received only if the peer provides its substance
+                if self.tool_id.is_none() {
+                    let mut bond = self.substance.bond(&ctx);
+                    let id = bond.add_tool::<Delegate>(self).await?;
+                    self.tool_id = Some(id);
+                    log::info!("The substance of {} is available as a tool", self.peer);
+                }
+            }
+            SubEvent::Event(()) => {}
+            SubEvent::Lost => {
+                self.detach();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Delegate {
+    question: String,
+}
+
+#[async_trait]
+impl Tool<Delegate> for PeerTool {
+    fn name(&self) -> String {
+        let peer = self.peer.to_string();
+        let suffix = &peer[peer.len().saturating_sub(8)..];
+        format!("substance_{suffix}")
+    }
+
+    fn description(&self) -> Option<String> {
+        Some(format!(
+            concat!(
+                "Delegates a question to the AI agent of the peer {}. ",
+                "The agent has its own models and tools, use it to solve sub-questions ",
+                "that the local tools can't answer. The function accepts a self-contained ",
+                "question and returns the agent's answer."
+            ),
+            self.peer
+        ))
+    }
+
+    fn parameters() -> Option<Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "question": {
+                    "type": "string",
+                    "description": "The self-contained question for the agent of the peer",
+                },
+            },
+            "required": ["question"],
+        }))
+    }
+
+    async fn handle_response(
+        &mut self,
+        msg: Delegate,
+        responder: Responder<ToolResponse>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        let peer = self.peer;
+        crb::core::spawn(async move {
+            let answer =
+                service::call::<DelegationService>(peer, msg.question, ANSWER_TIMEOUT).await;
+            let response = answer.map(|content| ToolResponse { content });
+            responder.send_result(response).ok();
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use derive_more::{Deref, DerefMut, From, Into};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use ui9::names::Fqn;
+use ui9_dui::{Flow, Listener, Publisher, Subscriber, Tracer, Unified};
+
+/// A cell shared between a particle and the substance.
+///
+/// The particle updates the status as soon as it changes and the substance
+/// reads it without interacting with the particle, so a particle that is busy
+/// with a long request is not considered unresponsive.
+#[derive(Debug, Clone)]
+pub struct HealthCell {
+    status: Arc<Mutex<HealthStatus>>,
+}
+
+impl HealthCell {
+    pub fn new(status: HealthStatus) -> Self {
+        Self {
+            status: Arc::new(Mutex::new(status)),
+        }
+    }
+
+    pub fn set(&self, status: HealthStatus) {
+        *self.lock() = status;
+    }
+
+    pub fn get(&self) -> HealthStatus {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HealthStatus> {
+        // The status is always valid, even if a writer has panicked
+        self.status
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum HealthStatus {
+    Healthy,
+    Degraded { reason: String },
+    Unhealthy { reason: String },
+}
+
+impl HealthStatus {
+    pub fn degraded(reason: impl ToString) -> Self {
+        Self::Degraded {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn unhealthy(reason: impl ToString) -> Self {
+        Self::Unhealthy {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, Self::Healthy)
+    }
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct HealthSub {
+    listener: Listener<Health>,
+}
+
+impl Subscriber for Health {
+    type Driver = HealthSub;
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct HealthPub {
+    tracer: Tracer<Health>,
+}
+
+impl Publisher for Health {
+    type Driver = HealthPub;
+}
+
+impl HealthPub {
+    pub fn set_status(&mut self, particle: &str, status: HealthStatus) {
+        let event = HealthEvent::SetStatus {
+            particle: particle.into(),
+            status,
+        };
+        self.tracer.event(event);
+    }
+
+    pub fn del_particle(&mut self, particle: &str) {
+        let event = HealthEvent::DelParticle {
+            particle: particle.into(),
+        };
+        self.tracer.event(event);
+    }
+}
+
+impl Unified for Health {
+    fn fqn() -> Fqn {
+        Fqn::root("@health")
+    }
+}
+
+/// Aggregated health of all particles of a substance.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Health {
+    pub particles: BTreeMap<String, HealthStatus>,
+}
+
+impl Health {
+    /// The worst status among particles.
+    pub fn overall(&self) -> HealthStatus {
+        self.particles
+            .values()
+            .max()
+            .cloned()
+            .unwrap_or(HealthStatus::Healthy)
+    }
+}
+
+impl Health {
+    /// Updates the status of the particle.
+    /// Returns `true` if the status has changed.
+    pub fn update(&mut self, particle: &str, status: HealthStatus) -> bool {
+        if self.particles.get(particle) == Some(&status) {
+            false
+        } else {
+            self.particles.insert(particle.into(), status);
+            true
+        }
+    }
+}
+
+impl Flow for Health {
+    type Event = HealthEvent;
+    type Action = ();
+
+    fn apply(&mut self, event: Self::Event) {
+        match event {
+            HealthEvent::SetStatus { particle, status } => {
+                self.particles.insert(particle, status);
+            }
+            HealthEvent::DelParticle { particle } => {
+                self.particles.remove(&particle);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HealthEvent {
+    SetStatus {
+        particle: String,
+        status: HealthStatus,
+    },
+    DelParticle {
+        particle: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overall_is_the_worst() {
+        let mut health = Health::default();
+        assert!(health.overall().is_healthy());
+
+        health.update("openai", HealthStatus::Healthy);
+        health.update("telegram", HealthStatus::degraded("Polling is not started"));
+        assert_eq!(
+            health.overall(),
+            HealthStatus::degraded("Polling is not started")
+        );
+
+        health.update("ollama", HealthStatus::unhealthy("No connection"));
+        assert_eq!(health.overall(), HealthStatus::unhealthy("No connection"));
+
+        health.apply(HealthEvent::DelParticle {
+            particle: "ollama".into(),
+        });
+        assert_eq!(
+            health.overall(),
+            HealthStatus::degraded("Polling is not started")
+        );
+    }
+
+    #[test]
+    fn test_update_reports_changes() {
+        let mut health = Health::default();
+        let cell = HealthCell::new(HealthStatus::degraded("Not configured yet"));
+        assert!(health.update("openai", cell.get()));
+        assert!(!health.update("openai", cell.get()));
+
+        // The particle changes the status through its own clone of the cell
+        cell.clone().set(HealthStatus::Healthy);
+        assert!(health.update("openai", cell.get()));
+        assert!(health.overall().is_healthy());
+    }
+}
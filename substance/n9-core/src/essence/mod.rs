@@ -1,4 +1,6 @@
+pub mod health;
 pub mod particle;
+pub mod restart;
 pub mod substance;
 
 use crate::keeper::KeeperLink;
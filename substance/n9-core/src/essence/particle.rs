@@ -1,9 +1,11 @@
+use super::health::HealthCell;
+use super::restart::RestartPolicy;
 use super::SubstanceLinks;
 use crate::keeper::subscription::ConfigSegmentUpdates;
 use crate::keeper::{subscription::UpdateConfig, Config};
 use crate::router::{
     model::Model,
-    tool::{CallParameters, Tool, ToolMeta},
+    tool::{CallParameters, Tool, ToolId, ToolMeta},
 };
 use anyhow::Result;
 use crb::agent::{Address, Agent, ToAddress};
@@ -27,6 +29,11 @@ pub trait Particle: Agent<Context: Default> {
         std::any::type_name::<Self>()
     }
 
+    /// The policy used if a particle was added without an explicit one.
+    fn restart_policy() -> RestartPolicy {
+        RestartPolicy::Never
+    }
+
     fn construct(substance: SubstanceLinks) -> Self;
 }
 
@@ -54,7 +61,25 @@ impl<A: Agent> SubstanceBond<A> {
         self.substance.router.add_model(address)
     }
 
-    pub async fn add_tool<P>(&mut self, tool: &A) -> Result<()>
+    /// Makes the particle a part of the aggregated substance health.
+    /// The particle reports its status by updating the cell.
+    pub fn add_health(&mut self, cell: &HealthCell) -> Result<()>
+    where
+        A: Particle,
+    {
+        self.substance.substance.add_health(A::name(), cell.clone())
+    }
+
+    /// Reports the particle has finished its work and stops by itself.
+    /// Call it before returning `Next::done()`.
+    pub fn done(&mut self) -> Result<()>
+    where
+        A: Particle,
+    {
+        self.substance.substance.particle_done(A::name())
+    }
+
+    pub async fn add_tool<P>(&mut self, tool: &A) -> Result<ToolId>
     where
         A: Tool<P>,
         P: CallParameters,
@@ -63,9 +88,8 @@ impl<A: Agent> SubstanceBond<A> {
         let meta = ToolMeta {
             name: tool.name(),
             description: tool.description(),
-            parameters: None,
+            parameters: <A as Tool<P>>::parameters(),
         };
-        self.substance.router.add_tool(address, meta).await?;
-        Ok(())
+        self.substance.router.add_tool(address, meta).await
     }
 }
@@ -0,0 +1,182 @@
+use crb::core::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// How a particle has stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// The particle has finished its work by itself.
+    Done,
+    /// The particle has failed or stopped without reporting it's done.
+    Failure,
+}
+
+/// Defines how the `Substance` treats a particle that has stopped
+/// while the substance itself is still alive.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum RestartPolicy {
+    /// The particle is never restarted.
+    #[default]
+    Never,
+    /// The particle is restarted with a backoff until retries are exhausted.
+    OnFailure(Backoff),
+    /// The particle is restarted with a backoff forever.
+    Always(Backoff),
+}
+
+impl RestartPolicy {
+    pub fn on_failure() -> Self {
+        Self::OnFailure(Backoff::default())
+    }
+
+    pub fn always() -> Self {
+        Self::Always(Backoff::default())
+    }
+
+    /// Returns a delay before the next attempt,
+    /// or `None` if the particle must not be restarted.
+    pub fn next_delay(&self, exit: Exit, attempt: u32) -> Option<Duration> {
+        match self {
+            Self::Never => None,
+            Self::OnFailure(_) if exit == Exit::Done => None,
+            Self::OnFailure(backoff) => {
+                let exhausted = backoff
+                    .max_retries
+                    .map(|max_retries| attempt >= max_retries)
+                    .unwrap_or_default();
+                (!exhausted).then(|| backoff.delay(attempt))
+            }
+            Self::Always(backoff) => Some(backoff.delay(attempt)),
+        }
+    }
+
+    /// A particle that worked longer than that is considered recovered
+    /// and the attempts counter is reset.
+    pub fn stable_after(&self) -> Option<Duration> {
+        match self {
+            Self::Never => None,
+            Self::OnFailure(backoff) | Self::Always(backoff) => Some(backoff.max_delay),
+        }
+    }
+}
+
+/// Exponential backoff parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backoff {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub factor: u32,
+    /// Ignored by `RestartPolicy::Always`.
+    pub max_retries: Option<u32>,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            factor: 2,
+            max_retries: Some(10),
+        }
+    }
+}
+
+impl Backoff {
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let multiplier = self.factor.max(1).saturating_pow(attempt);
+        self.initial_delay
+            .saturating_mul(multiplier)
+            .min(self.max_delay)
+    }
+}
+
+/// The restart policy of a particle as it's set in the config.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RestartConfig {
+    #[serde(default)]
+    pub policy: RestartMode,
+    /// Seconds before the first restart
+    #[serde(default)]
+    pub initial_delay: Option<u64>,
+    /// The maximal delay in seconds between restarts
+    #[serde(default)]
+    pub max_delay: Option<u64>,
+    #[serde(default)]
+    pub factor: Option<u32>,
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+impl From<RestartConfig> for RestartPolicy {
+    fn from(config: RestartConfig) -> Self {
+        let default = Backoff::default();
+        let backoff = Backoff {
+            initial_delay: config
+                .initial_delay
+                .map(Duration::from_secs)
+                .unwrap_or(default.initial_delay),
+            max_delay: config
+                .max_delay
+                .map(Duration::from_secs)
+                .unwrap_or(default.max_delay),
+            factor: config.factor.unwrap_or(default.factor),
+            max_retries: config.max_retries.or(default.max_retries),
+        };
+        match config.policy {
+            RestartMode::Never => Self::Never,
+            RestartMode::OnFailure => Self::OnFailure(backoff),
+            RestartMode::Always => Self::Always(backoff),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_growth() {
+        let backoff = Backoff::default();
+        assert_eq!(backoff.delay(0), Duration::from_secs(1));
+        assert_eq!(backoff.delay(3), Duration::from_secs(8));
+        assert_eq!(backoff.delay(30), backoff.max_delay);
+    }
+
+    #[test]
+    fn test_policy_attempts() {
+        assert_eq!(RestartPolicy::Never.next_delay(Exit::Failure, 0), None);
+
+        let backoff = Backoff {
+            max_retries: Some(2),
+            ..Backoff::default()
+        };
+        let policy = RestartPolicy::OnFailure(backoff.clone());
+        assert!(policy.next_delay(Exit::Failure, 1).is_some());
+        assert!(policy.next_delay(Exit::Failure, 2).is_none());
+        assert!(policy.next_delay(Exit::Done, 0).is_none());
+
+        let policy = RestartPolicy::Always(backoff);
+        assert!(policy.next_delay(Exit::Failure, 100).is_some());
+        assert!(policy.next_delay(Exit::Done, 0).is_some());
+    }
+
+    #[test]
+    fn test_policy_from_config() {
+        let config: RestartConfig =
+            toml::from_str("policy = \"on_failure\"\nmax_retries = 3").unwrap();
+        let policy = RestartPolicy::from(config);
+        let backoff = Backoff {
+            max_retries: Some(3),
+            ..Backoff::default()
+        };
+        assert_eq!(policy, RestartPolicy::OnFailure(backoff));
+    }
+}
@@ -1,4 +1,6 @@
+use super::health::{Health, HealthCell, HealthStatus};
 use super::particle::Particle;
+use super::restart::{Exit, RestartPolicy};
 use super::SubstanceLinks;
 use crate::keeper::Keeper;
 use crate::router::ReasoningRouter;
@@ -7,12 +9,22 @@ use crate::trace::TracerPack;
 use anyhow::{Error, Result};
 use async_trait::async_trait;
 use crb::agent::{
-    Address, Agent, AgentSession, Context, DoAsync, Equip, Next, OnEvent, Standalone,
+    Address, Agent, Context, DoAsync, Equip, ManagedContext, Next, OnEvent, RunAgent, Standalone,
 };
+use crb::core::time::{sleep, Instant};
 use crb::core::Slot;
-use crb::superagent::{InteractExt, OnRequest, Request, Supervisor, SupervisorSession};
+use crb::superagent::{
+    InteractExt, Interval, OnRequest, Relation, Request, StreamSession, Supervisor,
+    SupervisorSession, Tick,
+};
 use derive_more::{Deref, DerefMut, From, Into};
+use n9_std::config_stack::ConfigStack;
+use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
+use ui9_dui::reporter::Reporter;
+use ui9_dui::Pub;
+
+const HEALTH_INTERVAL_MS: u64 = 10_000;
 
 #[derive(Deref, DerefMut, From, Into, Clone)]
 pub struct SubstanceLink {
@@ -21,27 +33,67 @@ pub struct SubstanceLink {
 
 impl SubstanceLink {
     pub fn add_particle<P: Particle>(&self) -> Result<()> {
-        let msg = AddParticle::<P> { _type: PhantomData };
+        self.add_particle_with::<P>(P::restart_policy())
+    }
+
+    /// Adds a particle that will be supervised with the provided policy.
+    pub fn add_particle_with<P: Particle>(&self, policy: RestartPolicy) -> Result<()> {
+        let msg = AddParticle::<P> {
+            policy,
+            _type: PhantomData,
+        };
         self.address.event(msg)
     }
 
+    /// Marks the particle as finished by itself, so that it's not
+    /// restarted by the `RestartPolicy::OnFailure` policy when it stops.
+    pub fn particle_done(&self, particle: &'static str) -> Result<()> {
+        self.address.event(ParticleDone { particle })
+    }
+
     pub async fn be_particle(&self) -> Result<SubstanceLinks> {
         self.address.interact(BeParticle).await.map_err(Error::from)
     }
 
+    /// Returns the last known health of all particles.
+    pub async fn health(&self) -> Result<Health> {
+        self.address.interact(GetHealth).await.map_err(Error::from)
+    }
+
+    /// Adds a cell of the particle to the aggregated health.
+    pub fn add_health(&self, particle: &str, cell: HealthCell) -> Result<()> {
+        let msg = AddHealth {
+            particle: particle.into(),
+            cell,
+        };
+        self.address.event(msg)
+    }
+
     pub fn into_address(self) -> Address<Substance> {
         self.address
     }
 }
 
 pub struct Substance {
+    stack: ConfigStack,
     tracer: TracerPack,
     links: Slot<SubstanceLinks>,
+    particles: HashMap<Relation<Self>, ParticleRecord>,
+    interrupted: bool,
+    health: Pub<Health>,
+    health_state: Health,
+    health_cells: BTreeMap<String, HealthCell>,
+    interval: Interval,
 }
 
 impl Substance {
     pub fn arise() -> SubstanceLink {
-        Self::new().spawn().equip()
+        Self::arise_with(ConfigStack::default())
+    }
+
+    /// Starts a substance that reads configs from the provided layers.
+    pub fn arise_with(stack: ConfigStack) -> SubstanceLink {
+        Self::with_stack(stack).spawn().equip()
     }
 
     fn get_setup(&mut self) -> Result<SubstanceLinks> {
@@ -54,16 +106,44 @@ impl Standalone for Substance {}
 
 impl Substance {
     pub fn new() -> Self {
+        Self::with_stack(ConfigStack::default())
+    }
+
+    pub fn with_stack(stack: ConfigStack) -> Self {
         Self {
+            stack,
             tracer: TracerPack::root("substance"),
             links: Slot::empty(),
+            particles: HashMap::new(),
+            interrupted: false,
+            health: Pub::unified(),
+            health_state: Health::default(),
+            health_cells: BTreeMap::new(),
+            interval: Interval::new(),
         }
     }
 }
 
 impl Supervisor for Substance {
-    type BasedOn = AgentSession<Self>;
+    type BasedOn = StreamSession<Self>;
     type GroupBy = Group;
+
+    fn finished(&mut self, rel: &Relation<Self>, ctx: &mut Context<Self>) {
+        if let Some(record) = self.particles.remove(rel) {
+            if self.health_cells.remove(record.name).is_some() {
+                let status = HealthStatus::unhealthy("Particle stopped");
+                self.set_health(record.name, status);
+            }
+            if !self.interrupted {
+                let exit = if record.done {
+                    Exit::Done
+                } else {
+                    Exit::Failure
+                };
+                self.schedule_restart(record, exit, ctx);
+            }
+        }
+    }
 }
 
 impl Agent for Substance {
@@ -74,6 +154,12 @@ impl Agent for Substance {
         Next::do_async(Configure)
     }
 
+    fn interrupt(&mut self, ctx: &mut Context<Self>) {
+        // Particles stopped by the shutdown must not be restarted
+        self.interrupted = true;
+        ctx.shutdown();
+    }
+
     fn end(&mut self) {
         self.tracer.done();
     }
@@ -90,7 +176,7 @@ struct Configure;
 #[async_trait]
 impl DoAsync<Configure> for Substance {
     async fn handle(&mut self, _: Configure, ctx: &mut Context<Self>) -> Result<Next<Self>> {
-        let agent = Keeper::new();
+        let agent = Keeper::new(self.stack.clone());
         let keeper = ctx.spawn_agent(agent, Group::Services).equip();
 
         let agent = ReasoningRouter::new();
@@ -107,11 +193,100 @@ impl DoAsync<Configure> for Substance {
         };
         self.links.fill(links)?;
 
+        self.interval.set_interval_ms(HEALTH_INTERVAL_MS)?;
+        ctx.consume(self.interval.events()?);
+
         Ok(Next::events())
     }
 }
 
+type Spawner = fn(SubstanceLinks, &mut Context<Substance>) -> Relation<Substance>;
+
+struct ParticleRecord {
+    name: &'static str,
+    policy: RestartPolicy,
+    attempt: u32,
+    done: bool,
+    started: Instant,
+    spawner: Spawner,
+}
+
+impl ParticleRecord {
+    fn new<P: Particle>(policy: RestartPolicy) -> Self {
+        Self {
+            name: P::name(),
+            policy,
+            attempt: 0,
+            done: false,
+            started: Instant::now(),
+            spawner: spawn_particle::<P>,
+        }
+    }
+}
+
+fn spawn_particle<P: Particle>(
+    setup: SubstanceLinks,
+    ctx: &mut Context<Substance>,
+) -> Relation<Substance> {
+    let agent = P::construct(setup);
+    let runtime = RunAgent::new(agent);
+    ctx.spawn_trackable(runtime, Group::Particles)
+}
+
+impl Substance {
+    fn spawn_particle(
+        &mut self,
+        mut record: ParticleRecord,
+        ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        let setup = self.get_setup()?;
+        let rel = (record.spawner)(setup, ctx);
+        record.started = Instant::now();
+        record.done = false;
+        self.particles.insert(rel, record);
+        Ok(())
+    }
+
+    fn schedule_restart(
+        &mut self,
+        mut record: ParticleRecord,
+        exit: Exit,
+        ctx: &mut Context<Self>,
+    ) {
+        let name = record.name;
+        if let Some(stable_after) = record.policy.stable_after() {
+            if record.started.elapsed() >= stable_after {
+                record.attempt = 0;
+            }
+        }
+        match record.policy.next_delay(exit, record.attempt) {
+            Some(delay) => {
+                record.attempt += 1;
+                let attempt = record.attempt;
+                let message =
+                    format!("Particle {name} stopped, restart attempt {attempt} in {delay:?}");
+                log::warn!("{message}");
+                Reporter::failure(&message);
+                let address = ctx.address().clone();
+                crb::core::spawn(async move {
+                    sleep(delay).await;
+                    address.event(RestartParticle { record }).ok();
+                });
+            }
+            None if exit == Exit::Done => {
+                log::info!("Particle {name} is done");
+            }
+            None => {
+                let message = format!("Particle {name} stopped and won't be restarted");
+                log::error!("{message}");
+                Reporter::failure(&message);
+            }
+        }
+    }
+}
+
 struct AddParticle<P> {
+    policy: RestartPolicy,
     _type: PhantomData<P>,
 }
 
@@ -120,17 +295,98 @@ impl<P> OnEvent<AddParticle<P>> for Substance
 where
     P: Particle,
 {
-    async fn handle(&mut self, _: AddParticle<P>, ctx: &mut Context<Self>) -> Result<()> {
+    async fn handle(&mut self, msg: AddParticle<P>, ctx: &mut Context<Self>) -> Result<()> {
         let name = P::name();
         log::info!("Add particle: {name}");
-        let setup = self.get_setup()?;
-        let agent = P::construct(setup);
-        let _addr = ctx.spawn_agent(agent, Group::Particles);
+        let record = ParticleRecord::new::<P>(msg.policy);
+        self.spawn_particle(record, ctx)?;
         // Hub::log(&format!("Particle ***{name}*** is added"));
         Ok(())
     }
 }
 
+struct RestartParticle {
+    record: ParticleRecord,
+}
+
+#[async_trait]
+impl OnEvent<RestartParticle> for Substance {
+    async fn handle(&mut self, msg: RestartParticle, ctx: &mut Context<Self>) -> Result<()> {
+        if !self.interrupted {
+            let name = msg.record.name;
+            let attempt = msg.record.attempt;
+            log::info!("Restart particle: {name} (attempt {attempt})");
+            self.spawn_particle(msg.record, ctx)?;
+        }
+        Ok(())
+    }
+}
+
+struct ParticleDone {
+    particle: &'static str,
+}
+
+#[async_trait]
+impl OnEvent<ParticleDone> for Substance {
+    async fn handle(&mut self, msg: ParticleDone, _ctx: &mut Context<Self>) -> Result<()> {
+        let records = self.particles.values_mut();
+        for record in records.filter(|record| record.name == msg.particle) {
+            record.done = true;
+        }
+        Ok(())
+    }
+}
+
+impl Substance {
+    fn set_health(&mut self, particle: &str, status: HealthStatus) {
+        if self.health_state.update(particle, status.clone()) {
+            self.health.set_status(particle, status);
+        }
+    }
+}
+
+struct AddHealth {
+    particle: String,
+    cell: HealthCell,
+}
+
+#[async_trait]
+impl OnEvent<AddHealth> for Substance {
+    async fn handle(&mut self, msg: AddHealth, _ctx: &mut Context<Self>) -> Result<()> {
+        self.set_health(&msg.particle, msg.cell.get());
+        self.health_cells.insert(msg.particle, msg.cell);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OnEvent<Tick> for Substance {
+    async fn handle(&mut self, _: Tick, _ctx: &mut Context<Self>) -> Result<()> {
+        let statuses: Vec<_> = self
+            .health_cells
+            .iter()
+            .map(|(particle, cell)| (particle.clone(), cell.get()))
+            .collect();
+        for (particle, status) in statuses {
+            self.set_health(&particle, status);
+        }
+        Ok(())
+    }
+}
+
+struct GetHealth;
+
+impl Request for GetHealth {
+    type Response = Health;
+}
+
+#[async_trait]
+impl OnRequest<GetHealth> for Substance {
+    async fn on_request(&mut self, _: GetHealth, _ctx: &mut Context<Self>) -> Result<Health> {
+        Ok(self.health_state.clone())
+    }
+}
+
 struct BeParticle;
 
 impl Request for BeParticle {
@@ -1,8 +1,14 @@
+use super::secret::{is_secret, redact, resolve_secrets};
 use super::{Config, Keeper, KeeperLink};
 use anyhow::Result;
 use async_trait::async_trait;
 use crb::agent::Context;
 use crb::superagent::{InteractExt, OnRequest, Request};
+use n9_std::config_coerce::{from_config, Coerce};
+use n9_std::config_loader::wrap_level;
+use n9_std::config_schema::{FieldDoc, KeyDoc};
+use n9_std::diagnostics::SegmentError;
+use serde_path_to_error::Segment;
 use toml::Value;
 
 impl KeeperLink {
@@ -11,7 +17,7 @@ impl KeeperLink {
         C: Config,
     {
         let request = GetConfig::new::<C>()?;
-        let config = self.address.interact(request).await?.try_into()?;
+        let config = from_config(self.address.interact(request).await?)?;
         Ok(config)
     }
 }
@@ -19,6 +25,9 @@ impl KeeperLink {
 pub struct GetConfig {
     pub namespace: String,
     pub template: Value,
+    pub secrets: &'static [&'static str],
+    pub fields: &'static [FieldDoc],
+    pub validate: fn(&Value) -> Result<(), SegmentError>,
 }
 
 impl GetConfig {
@@ -28,8 +37,105 @@ impl GetConfig {
         Ok(Self {
             namespace,
             template,
+            secrets: C::SECRETS,
+            fields: C::FIELDS,
+            validate: validate::<C>,
         })
     }
+
+    /// The path to the segment in the merged config.
+    pub fn path(&self) -> Vec<String> {
+        vec!["particle".into(), self.namespace.clone(), "config".into()]
+    }
+
+    /// The segment in the merged config or the template if the segment is missing.
+    pub fn segment(&self, config: &Value) -> Value {
+        config
+            .get("particle")
+            .and_then(|particle| particle.get(&self.namespace))
+            .and_then(|namespace| namespace.get("config"))
+            .cloned()
+            .unwrap_or_else(|| self.template.clone())
+    }
+
+    /// Resolves secrets of the segment and checks it could be used.
+    /// Messages about secret fields are masked, since they may contain the value.
+    pub fn check(&self, value: &mut Value) -> Result<(), SegmentError> {
+        resolve_secrets(value)
+            .and_then(|()| (self.validate)(value))
+            .map_err(|mut error| {
+                if is_secret(&error.key, self.secrets) {
+                    error.message = "The secret value is invalid".into();
+                }
+                error
+            })
+    }
+
+    /// The template placed at the path of the segment with redacted secrets.
+    pub fn scoped_template(&self) -> Value {
+        let mut template = self.template.clone();
+        redact(&mut template, self.secrets);
+        let config = wrap_level("config", template);
+        let scoped = wrap_level(&self.namespace, config);
+        wrap_level("particle", scoped)
+    }
+
+    /// Documentation of fields with defaults of the template.
+    pub fn key_docs(&self) -> Vec<KeyDoc> {
+        let mut docs = Vec::new();
+        for field in self.fields {
+            let mut default = Some(&self.template);
+            for part in field.key.split('.') {
+                default = default.and_then(|value| value.get(part));
+            }
+            let mut key = self.path();
+            key.extend(field.key.split('.').map(String::from));
+            let secret = [field.key.to_string()];
+            docs.push(KeyDoc {
+                key,
+                description: field.description.into(),
+                values: field.values.iter().map(|value| value.to_string()).collect(),
+                default: default
+                    .filter(|_| !is_secret(&secret, self.secrets))
+                    .cloned(),
+            });
+        }
+        docs
+    }
+
+    /// Full paths to secret fields in the merged config.
+    pub fn secret_keys(&self) -> Vec<Vec<String>> {
+        self.secrets
+            .iter()
+            .map(|secret| {
+                let mut key = self.path();
+                key.extend(secret.split('.').map(String::from));
+                key
+            })
+            .collect()
+    }
+}
+
+/// Checks the segment could be deserialized into the config.
+fn validate<C: Config>(value: &Value) -> Result<(), SegmentError> {
+    serde_path_to_error::deserialize::<_, C>(Coerce(value.clone()))
+        .map(drop)
+        .map_err(|err| {
+            let key = err
+                .path()
+                .iter()
+                .filter_map(|segment| match segment {
+                    Segment::Seq { index } => Some(index.to_string()),
+                    Segment::Map { key } => Some(key.clone()),
+                    Segment::Enum { variant } => Some(variant.clone()),
+                    Segment::Unknown => None,
+                })
+                .collect();
+            SegmentError {
+                key,
+                message: err.into_inner().message().to_string(),
+            }
+        })
 }
 
 impl Request for GetConfig {
@@ -39,7 +145,7 @@ impl Request for GetConfig {
 #[async_trait]
 impl OnRequest<GetConfig> for Keeper {
     async fn on_request(&mut self, msg: GetConfig, _: &mut Context<Self>) -> Result<Value> {
-        let config = self.config.get_config_segment(&msg);
+        let config = self.valid_segment(&msg);
         Ok(config)
     }
 }
@@ -1,4 +1,5 @@
 pub mod interaction;
+pub mod secret;
 pub mod subscription;
 
 use anyhow::Result;
@@ -8,7 +9,10 @@ use crb::core::{Slot, Unique};
 use crb::superagent::{Entry, SubscribeExt, Supervisor, SupervisorSession};
 use derive_more::{Deref, DerefMut, From};
 use interaction::GetConfig;
-use n9_std::config_loader::{ConfigLoader, ConfigUpdates, NewConfig};
+use n9_std::config_loader::{ConfigLoader, ConfigUpdates, NewConfig, SegmentStatus};
+pub use n9_std::config_profile::{MergedConfig, ACTIVE_PROFILE_KEY, PROFILES_KEY};
+use n9_std::config_schema::FieldDoc;
+use n9_std::config_stack::ConfigStack;
 use serde::{de::DeserializeOwned, Serialize};
 use std::collections::HashMap;
 use subscription::{ConfigSegmentUpdates, Subscriber};
@@ -19,6 +23,13 @@ pub trait Config: DeserializeOwned + Serialize + Send + 'static {
     // TODO: Add methods to get a full path for logging
     const NAMESPACE: &str;
 
+    /// Dotted paths to secret fields of the config.
+    /// Their values are redacted in templates and diagnostics.
+    const SECRETS: &'static [&'static str] = &[];
+
+    /// Documentation of fields placed in templates and schemas.
+    const FIELDS: &'static [FieldDoc] = &[];
+
     fn template() -> Self;
 }
 
@@ -27,33 +38,51 @@ pub struct KeeperLink {
     address: Address<Keeper>,
 }
 
-#[derive(From)]
-pub struct MergedConfig {
-    value: Value,
-}
-
-impl MergedConfig {
-    fn new() -> Self {
-        Self {
-            value: Value::Table(Table::new()),
-        }
-    }
-}
-
 pub struct Keeper {
-    config: MergedConfig,
+    stack: ConfigStack,
+    /// The merged config with the active profile applied by the loader
+    config: Value,
     updater: Slot<Entry<ConfigUpdates>>,
     subscribers: HashMap<Unique<ConfigSegmentUpdates>, Subscriber>,
     loader: Slot<Address<ConfigLoader>>,
+    /// The last segments that were successfully validated
+    last_valid: HashMap<String, Value>,
 }
 
 impl Keeper {
-    pub fn new() -> Self {
+    pub fn new(stack: ConfigStack) -> Self {
         Self {
-            config: MergedConfig::new(),
+            stack,
+            config: Value::Table(Table::new()),
             updater: Slot::empty(),
             subscribers: HashMap::new(),
             loader: Slot::empty(),
+            last_valid: HashMap::new(),
+        }
+    }
+
+    /// Returns the segment if it's valid, or the last valid one otherwise.
+    /// The result of the validation is reported to the loader.
+    fn valid_segment(&mut self, seg: &GetConfig) -> Value {
+        let mut value = seg.segment(&self.config);
+        let result = seg.check(&mut value);
+        if let Ok(loader) = self.loader.get() {
+            let status = SegmentStatus {
+                segment: seg.path(),
+                error: result.clone().err(),
+            };
+            loader.event(status).ok();
+        }
+        match result {
+            Ok(()) => {
+                self.last_valid.insert(seg.namespace.clone(), value.clone());
+                value
+            }
+            Err(_) => self
+                .last_valid
+                .get(&seg.namespace)
+                .cloned()
+                .unwrap_or_else(|| seg.template.clone()),
         }
     }
 }
@@ -76,38 +105,23 @@ struct Initialize;
 #[async_trait]
 impl DoAsync<Initialize> for Keeper {
     async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
-        let loader = ConfigLoader::new();
+        let loader = ConfigLoader::new(self.stack.clone());
         let (addr, _) = ctx.spawn_agent(loader, ());
         let sub = ConfigUpdates::for_listener(ctx);
         let state_entry = addr.subscribe(sub).await?;
         self.loader.fill(addr)?;
 
         // No subscribers here, not necessary to distribute the config
-        self.config = MergedConfig::from(state_entry.state);
+        self.config = state_entry.state;
         self.updater.fill(state_entry.entry)?;
         Ok(Next::events())
     }
 }
 
-impl MergedConfig {
-    fn get_config_segment(&self, seg: &GetConfig) -> Value {
-        self.get_config_segment_opt(seg)
-            .unwrap_or_else(|| seg.template.clone())
-    }
-
-    fn get_config_segment_opt(&self, seg: &GetConfig) -> Option<Value> {
-        self.value
-            .get("particle")?
-            .get(&seg.namespace)?
-            .get("config")
-            .cloned()
-    }
-}
-
 #[async_trait]
 impl OnEvent<NewConfig> for Keeper {
     async fn handle(&mut self, config: NewConfig, _ctx: &mut Context<Self>) -> Result<()> {
-        self.config = MergedConfig::from(config.0);
+        self.config = config.0;
         self.distribute();
         Ok(())
     }
@@ -0,0 +1,126 @@
+pub use n9_std::config_flow::REDACTED;
+use n9_std::diagnostics::SegmentError;
+use std::env;
+use std::fs;
+use toml::Value;
+
+const SECRET_KEY: &str = "secret";
+const ENV_KEY: &str = "env";
+
+enum Reference<'a> {
+    File(&'a str),
+    Env(&'a str),
+}
+
+impl<'a> Reference<'a> {
+    /// Detects `{ secret = "file:/path" }`, `{ secret = "env:NAME" }` or `{ env = "NAME" }`.
+    fn detect(value: &'a Value) -> Option<Self> {
+        let table = value.as_table()?;
+        if table.len() != 1 {
+            return None;
+        }
+        if let Some(name) = table.get(ENV_KEY).and_then(Value::as_str) {
+            return Some(Self::Env(name));
+        }
+        let secret = table.get(SECRET_KEY)?.as_str()?;
+        if let Some(path) = secret.strip_prefix("file:") {
+            Some(Self::File(path))
+        } else if let Some(name) = secret.strip_prefix("env:") {
+            Some(Self::Env(name))
+        } else {
+            None
+        }
+    }
+
+    fn resolve(&self) -> Result<String, String> {
+        match self {
+            Self::File(path) => fs::read_to_string(path)
+                .map(|content| content.trim_end().to_string())
+                .map_err(|err| format!("Can't read the secret file {path}: {err}")),
+            Self::Env(name) => env::var(name)
+                .map_err(|err| format!("Can't read the secret variable {name}: {err}")),
+        }
+    }
+}
+
+/// Replaces all secret references of the segment with their values.
+pub fn resolve_secrets(value: &mut Value) -> Result<(), SegmentError> {
+    let mut path = Vec::new();
+    resolve_at(value, &mut path)
+}
+
+fn resolve_at(value: &mut Value, path: &mut Vec<String>) -> Result<(), SegmentError> {
+    if let Some(reference) = Reference::detect(value) {
+        let secret = reference.resolve().map_err(|message| SegmentError {
+            key: path.clone(),
+            message,
+        })?;
+        *value = Value::String(secret);
+        return Ok(());
+    }
+    match value {
+        Value::Table(table) => {
+            for (key, item) in table.iter_mut() {
+                path.push(key.clone());
+                resolve_at(item, path)?;
+                path.pop();
+            }
+        }
+        Value::Array(array) => {
+            for (index, item) in array.iter_mut().enumerate() {
+                path.push(index.to_string());
+                resolve_at(item, path)?;
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Replaces values of secret fields of the segment.
+/// References are kept, since they don't contain secrets.
+pub fn redact(value: &mut Value, secrets: &[&str]) {
+    for secret in secrets {
+        let mut item = Some(&mut *value);
+        for key in secret.split('.') {
+            item = item.and_then(|item| item.get_mut(key));
+        }
+        if let Some(item) = item {
+            if Reference::detect(item).is_none() {
+                *item = Value::String(REDACTED.into());
+            }
+        }
+    }
+}
+
+/// Checks the key belongs to a secret field.
+pub fn is_secret(key: &[String], secrets: &[&str]) -> bool {
+    let key = key.join(".");
+    secrets
+        .iter()
+        .any(|secret| key == *secret || key.starts_with(&format!("{secret}.")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_and_redact() {
+        env::set_var("N9_TEST_SECRET", "sk-test");
+        let mut value: Value = toml::from_str(
+            r#"
+            api_key = { env = "N9_TEST_SECRET" }
+            model = "gpt-4o"
+            "#,
+        )
+        .unwrap();
+        resolve_secrets(&mut value).unwrap();
+        assert_eq!(value["api_key"].as_str(), Some("sk-test"));
+
+        redact(&mut value, &["api_key"]);
+        assert_eq!(value["api_key"].as_str(), Some(REDACTED));
+        assert_eq!(value["model"].as_str(), Some("gpt-4o"));
+    }
+}
@@ -6,7 +6,10 @@ use crb::agent::{Agent, Context, MessageFor, ToAddress};
 use crb::core::Unique;
 use crb::send::{Recipient, Sender};
 use crb::superagent::{Entry, ManageSubscription, SubscribeExt, Subscription};
-use n9_std::config_loader::{merge_configs, table, wrap_level, StoreTemplate};
+use n9_std::config_coerce::from_config;
+use n9_std::config_loader::{merge_configs, table, ConfigSecrets, StoreTemplate};
+use n9_std::config_schema::KeyDoc;
+use n9_std::config_stack::SegmentSecrets;
 use std::any::type_name;
 use std::marker::PhantomData;
 use toml::Value;
@@ -38,7 +41,7 @@ impl KeeperLink {
             recipient: Recipient::new(recipient),
         };
         let state_entry = self.subscribe(updates).await?;
-        let config = state_entry.state.try_into()?;
+        let config = from_config(state_entry.state)?;
         Ok((config, state_entry.entry))
     }
 }
@@ -63,27 +66,45 @@ pub struct Subscriber {
 
 impl Keeper {
     pub fn distribute(&mut self) {
-        for (id, subscriber) in &mut self.subscribers {
-            let value = self.config.get_config_segment(&id.get_config);
-            if subscriber.last_value.as_ref() == Some(&value) {
+        let mut subscribers = std::mem::take(&mut self.subscribers);
+        for (id, subscriber) in &mut subscribers {
+            // Invalid segments are not delivered, subscribers keep the last valid one
+            let value = self.valid_segment(&id.get_config);
+            if subscriber.last_value.as_ref() != Some(&value) {
                 subscriber.last_value = Some(value.clone());
+                id.recipient.send(NewConfigSegment(value)).ok();
             }
-            id.recipient.send(NewConfigSegment(value.clone())).ok();
         }
+        self.subscribers = subscribers;
     }
 }
 
 impl Keeper {
     fn merged_template(&self) -> Value {
-        let mut particles = table();
+        let mut template = table();
         for (id, _) in &self.subscribers {
-            let template = id.get_config.template.clone();
-            let config = wrap_level("config", template);
-            let scope = &id.get_config.namespace;
-            let scoped = wrap_level(scope, config);
-            merge_configs(&mut particles, &scoped);
+            merge_configs(&mut template, &id.get_config.scoped_template());
         }
-        wrap_level("particle", particles)
+        template
+    }
+
+    /// Documentation of fields of all consumers with defaults of the template.
+    fn key_docs(&self) -> Vec<KeyDoc> {
+        let mut docs: Vec<_> = self
+            .subscribers
+            .keys()
+            .flat_map(|id| id.get_config.key_docs())
+            .collect();
+        docs.sort_by(|a, b| a.key.cmp(&b.key));
+        docs
+    }
+
+    /// Secret fields of all consumers by their namespaces.
+    fn secret_keys(&self) -> SegmentSecrets {
+        self.subscribers
+            .keys()
+            .map(|id| (id.get_config.namespace.clone(), id.get_config.secret_keys()))
+            .collect()
     }
 }
 
@@ -94,12 +115,19 @@ impl ManageSubscription<ConfigSegmentUpdates> for Keeper {
         sub_id: Unique<ConfigSegmentUpdates>,
         _ctx: &mut Context<Self>,
     ) -> Result<Value> {
-        let subscriber = Subscriber { last_value: None };
-        let value = self.config.get_config_segment(&sub_id.get_config);
+        let value = self.valid_segment(&sub_id.get_config);
+        let subscriber = Subscriber {
+            last_value: Some(value.clone()),
+        };
         self.subscribers.insert(sub_id, subscriber);
 
-        let template = self.merged_template();
-        let msg = StoreTemplate(template);
+        let msg = StoreTemplate {
+            template: self.merged_template(),
+            docs: self.key_docs(),
+        };
+        self.loader.get()?.event(msg)?;
+
+        let msg = ConfigSecrets(self.secret_keys());
         self.loader.get()?.event(msg)?;
         Ok(value)
     }
@@ -144,7 +172,7 @@ where
     C: Config,
 {
     async fn handle(self: Box<Self>, agent: &mut A, ctx: &mut Context<A>) -> Result<()> {
-        let result = match self.value.try_into() {
+        let result = match from_config(self.value) {
             Ok(config) => agent.update_config(config, ctx).await,
             Err(err) => {
                 let ns = C::NAMESPACE;
@@ -5,13 +5,17 @@ pub mod sequence;
 pub mod space;
 pub mod trace;
 
+pub use essence::health::{Health, HealthCell, HealthStatus};
 pub use essence::particle::{Particle, SubstanceBond};
+pub use essence::restart::{Backoff, Exit, RestartConfig, RestartMode, RestartPolicy};
 pub use essence::substance::{Substance, SubstanceLink};
 pub use essence::SubstanceLinks;
 pub use keeper::subscription::{ConfigSegmentUpdates, UpdateConfig};
 pub use keeper::{Config, Keeper, KeeperLink};
-pub use router::model::{Model, ModelLink};
-pub use router::tool::{Tool, ToolLink, ToolMeta, ToolResponse};
+pub use n9_std::config_schema::FieldDoc;
+pub use n9_std::config_stack::{ConfigArgs, ConfigStack, SegmentSecrets};
+pub use router::model::{Model, ModelAddress, ModelId, ModelInfo, ModelLink, ModelMeta};
+pub use router::tool::{Tool, ToolId, ToolInfo, ToolLink, ToolMeta, ToolMetaWithId, ToolResponse};
 pub use router::types::{
-    ChatRequest, ChatResponse, Message, Role, ToolingChatRequest, ToolingChatResponse,
+    ChatRequest, ChatResponse, Message, Role, ToolCall, ToolingChatRequest, ToolingChatResponse,
 };
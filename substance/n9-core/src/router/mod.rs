@@ -8,7 +8,7 @@ use async_trait::async_trait;
 use crb::agent::{Address, Agent, AgentSession, Context, Equip, Next};
 use crb::superagent::{InteractExt, OnRequest, Request, Responder, Supervisor, SupervisorSession};
 use derive_more::{Deref, DerefMut, From, Into};
-use model::ModelLink;
+use model::ModelRecord;
 use session::{ReasoningSession, SessionLink};
 use std::collections::HashMap;
 use tool::{ToolId, ToolRecord};
@@ -24,8 +24,10 @@ pub struct RouterLink {
 }
 
 pub struct ReasoningRouter {
-    models: Vec<ModelLink>,
+    models: Vec<ModelRecord>,
+    models_added: usize,
     tools: HashMap<ToolId, ToolRecord>,
+    tools_added: usize,
     requests: TypedSlab<ReqId, Responder<ChatResponse>>,
 }
 
@@ -33,7 +35,9 @@ impl ReasoningRouter {
     pub fn new() -> Self {
         Self {
             models: Vec::default(),
+            models_added: 0,
             tools: HashMap::default(),
+            tools_added: 0,
             requests: TypedSlab::default(),
         }
     }
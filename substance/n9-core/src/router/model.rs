@@ -5,6 +5,7 @@ use async_trait::async_trait;
 use crb::agent::{Address, Context, Equip, OnEvent};
 use crb::superagent::{Fetcher, InteractExt, OnRequest, Request};
 use derive_more::{Deref, DerefMut};
+use std::any::type_name;
 use std::sync::Arc;
 
 pub trait Model: OnRequest<ToolingChatRequest> {}
@@ -22,6 +23,14 @@ impl<M: Model> From<Address<M>> for ModelLink {
     }
 }
 
+impl ModelLink {
+    pub fn new(address: impl ModelAddress + 'static) -> Self {
+        Self {
+            address: Arc::new(address),
+        }
+    }
+}
+
 pub trait ModelAddress: Sync + Send {
     fn chat(&self, request: ToolingChatRequest) -> Fetcher<ToolingChatResponse>;
 }
@@ -32,6 +41,51 @@ impl<M: Model> ModelAddress for Address<M> {
     }
 }
 
+pub type ModelId = String;
+
+#[derive(Debug, Clone)]
+pub struct ModelMeta {
+    pub name: String,
+    /// The peer that hosts the model, if it's not a local one.
+    pub remote: Option<String>,
+}
+
+impl ModelMeta {
+    pub fn local<M>() -> Self {
+        let name = type_name::<M>().rsplit("::").next().unwrap_or_default();
+        Self {
+            name: name.into(),
+            remote: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModelInfo {
+    pub id: ModelId,
+    pub meta: ModelMeta,
+}
+
+pub struct ModelRecord {
+    info: ModelInfo,
+    link: ModelLink,
+}
+
+impl ReasoningRouter {
+    fn insert_model(&mut self, link: ModelLink, meta: ModelMeta) -> ModelInfo {
+        let id = ModelId::from(format!("{}_{}", meta.name, self.models_added));
+        self.models_added += 1;
+        let info = ModelInfo { id, meta };
+        log::info!("Model added: {} (remote: {:?})", info.id, info.meta.remote);
+        let record = ModelRecord {
+            info: info.clone(),
+            link,
+        };
+        self.models.push(record);
+        info
+    }
+}
+
 impl RouterLink {
     // TODO: Return model detacher (calls remove_model)
     // Use subscriptions management to control model existence
@@ -39,30 +93,93 @@ impl RouterLink {
     where
         M: Model,
     {
-        let msg = AddModel { link: addr.equip() };
+        let msg = AddModel {
+            link: addr.equip(),
+            meta: ModelMeta::local::<M>(),
+        };
         // TODO: Use interaction instead
         self.address.event(msg)?;
         Ok(())
     }
 
+    /// Adds a model with a custom address, e.g. a proxy to a remote one.
+    pub async fn add_model_link(&mut self, link: ModelLink, meta: ModelMeta) -> Result<ModelId> {
+        let msg = AddModelLink { link, meta };
+        let info = self.interact(msg).await?;
+        Ok(info.id)
+    }
+
+    pub fn remove_model(&mut self, id: ModelId) -> Result<()> {
+        let msg = RemoveModel { id };
+        self.address.event(msg)?;
+        Ok(())
+    }
+
+    /// Returns a model to use. Local models are preferred.
     pub async fn get_model(&mut self) -> Result<ModelLink> {
-        self.interact(GetModel).await.map_err(Error::from)
+        let msg = GetModel { local_only: false };
+        self.interact(msg).await.map_err(Error::from)
+    }
+
+    pub async fn get_local_model(&mut self) -> Result<ModelLink> {
+        let msg = GetModel { local_only: true };
+        self.interact(msg).await.map_err(Error::from)
+    }
+
+    pub async fn get_models(&mut self) -> Result<Vec<ModelInfo>> {
+        self.interact(GetModels).await.map_err(Error::from)
     }
 }
 
 pub struct AddModel {
     link: ModelLink,
+    meta: ModelMeta,
 }
 
 #[async_trait]
 impl OnEvent<AddModel> for ReasoningRouter {
     async fn handle(&mut self, msg: AddModel, _ctx: &mut Context<Self>) -> Result<()> {
-        self.models.push(msg.link);
+        self.insert_model(msg.link, msg.meta);
         Ok(())
     }
 }
 
-struct GetModel;
+pub struct AddModelLink {
+    link: ModelLink,
+    meta: ModelMeta,
+}
+
+impl Request for AddModelLink {
+    type Response = ModelInfo;
+}
+
+#[async_trait]
+impl OnRequest<AddModelLink> for ReasoningRouter {
+    async fn on_request(
+        &mut self,
+        msg: AddModelLink,
+        _ctx: &mut Context<Self>,
+    ) -> Result<ModelInfo> {
+        Ok(self.insert_model(msg.link, msg.meta))
+    }
+}
+
+pub struct RemoveModel {
+    id: ModelId,
+}
+
+#[async_trait]
+impl OnEvent<RemoveModel> for ReasoningRouter {
+    async fn handle(&mut self, msg: RemoveModel, _ctx: &mut Context<Self>) -> Result<()> {
+        self.models.retain(|record| record.info.id != msg.id);
+        log::info!("Model removed: {}", msg.id);
+        Ok(())
+    }
+}
+
+struct GetModel {
+    local_only: bool,
+}
 
 impl Request for GetModel {
     type Response = ModelLink;
@@ -70,10 +187,39 @@ impl Request for GetModel {
 
 #[async_trait]
 impl OnRequest<GetModel> for ReasoningRouter {
-    async fn on_request(&mut self, _: GetModel, ctx: &mut Context<Self>) -> Result<ModelLink> {
-        self.models
-            .first()
-            .cloned()
+    async fn on_request(&mut self, msg: GetModel, ctx: &mut Context<Self>) -> Result<ModelLink> {
+        let local = self
+            .models
+            .iter()
+            .find(|record| record.info.meta.remote.is_none());
+        let record = if msg.local_only {
+            local
+        } else {
+            local.or_else(|| self.models.first())
+        };
+        record
+            .map(|record| record.link.clone())
             .ok_or_else(|| anyhow!("Models are not installed"))
     }
 }
+
+struct GetModels;
+
+impl Request for GetModels {
+    type Response = Vec<ModelInfo>;
+}
+
+#[async_trait]
+impl OnRequest<GetModels> for ReasoningRouter {
+    async fn on_request(
+        &mut self,
+        _: GetModels,
+        _ctx: &mut Context<Self>,
+    ) -> Result<Vec<ModelInfo>> {
+        Ok(self
+            .models
+            .iter()
+            .map(|record| record.info.clone())
+            .collect())
+    }
+}
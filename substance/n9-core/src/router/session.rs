@@ -1,5 +1,5 @@
 use super::{ChatRequest, ChatResponse, RouterLink};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use crb::agent::{Address, Agent, AgentSession, Context, Next, StopAddress};
 use crb::superagent::{Fetcher, InteractExt, OnRequest};
@@ -24,6 +24,9 @@ impl SessionLink {
     }
 }
 
+/// Limits rounds of tool calls if a model keeps calling them.
+const MAX_TOOL_ROUNDS: usize = 8;
+
 pub struct ReasoningSession {
     router: RouterLink,
 }
@@ -51,9 +54,20 @@ impl OnRequest<ChatRequest> for ReasoningSession {
     ) -> Result<ChatResponse> {
         let model = self.router.get_model().await?;
         let tools = self.router.get_tools().await?;
-        let request = request.with_tools(tools);
-        let response = model.chat(request).await?;
-        let response = response.without_tools();
-        Ok(response)
+        let mut request = request.with_tools(tools);
+        for _ in 0..MAX_TOOL_ROUNDS {
+            let response = model.chat(request.clone()).await?;
+            let calls = response.tool_calls();
+            if calls.is_empty() {
+                return Ok(response.without_tools());
+            }
+            // Results are sent back to the model with its calls
+            request.messages.extend(response.messages);
+            let results = self.router.call_tools(calls).await?;
+            request.messages.extend(results);
+        }
+        Err(anyhow!(
+            "The model keeps calling tools after {MAX_TOOL_ROUNDS} rounds"
+        ))
     }
 }
@@ -1,12 +1,14 @@
+use super::types::{Message, ToolCall};
 use super::{ReasoningRouter, RouterLink};
 use anyhow::{anyhow, Error, Result};
 use async_trait::async_trait;
-use crb::agent::{Address, Agent, Context, MessageFor};
+use crb::agent::{Address, Agent, Context, MessageFor, OnEvent};
 use crb::send::{Recipient, Sender};
 use crb::superagent::{
     Fetcher, InteractExt, Interaction, Interplay, OnRequest, Request, Responder,
 };
 use derive_more::{Deref, DerefMut};
+use futures::future::join_all;
 use serde::de::DeserializeOwned;
 use serde_json::Value;
 use std::any::type_name;
@@ -36,6 +38,11 @@ where
         None
     }
 
+    /// JSON schema of parameters that models have to provide.
+    fn parameters() -> Option<Value> {
+        None
+    }
+
     async fn handle_request(
         &mut self,
         // TODO: Use a custom wrapper for `Interplay`
@@ -117,9 +124,44 @@ impl RouterLink {
         Ok(response.info.id.clone())
     }
 
+    pub fn remove_tool(&mut self, id: ToolId) -> Result<()> {
+        let msg = RemoveTool { id };
+        self.address.event(msg)?;
+        Ok(())
+    }
+
     pub async fn get_tools(&mut self) -> Result<Vec<ToolInfo>> {
         self.interact(GetTools).await.map_err(Error::from)
     }
+
+    /// Calls tools concurrently and returns their results as tool messages.
+    /// Failures are reported in messages to let the model handle them.
+    pub async fn call_tools(&mut self, calls: Vec<ToolCall>) -> Result<Vec<Message>> {
+        let ids = calls.iter().map(|call| call.tool.clone()).collect();
+        let links = self.interact(GetToolLinks { ids }).await?;
+        let results = calls.into_iter().zip(links).map(|(call, link)| async move {
+            let result = match link {
+                Some(link) => call_tool(&link, &call.arguments).await,
+                None => Err(anyhow!("Tool {} is not installed", call.tool)),
+            };
+            let content = match result {
+                Ok(response) => response.content,
+                Err(err) => format!("Error: {err}"),
+            };
+            Message::tool_result(call.id, content)
+        });
+        Ok(join_all(results).await)
+    }
+}
+
+async fn call_tool(link: &ToolLink, arguments: &str) -> Result<ToolResponse> {
+    // Models could send an empty string for tools without parameters
+    let value = if arguments.trim().is_empty() {
+        Value::Object(Default::default())
+    } else {
+        serde_json::from_str(arguments)?
+    };
+    link.call_tool(value).await.map_err(Error::from)
 }
 
 pub type ToolId = String;
@@ -163,7 +205,8 @@ pub struct ToolRecord {
 #[async_trait]
 impl OnRequest<AddTool> for ReasoningRouter {
     async fn on_request(&mut self, msg: AddTool, _ctx: &mut Context<Self>) -> Result<ToolAdded> {
-        let id = ToolId::from(format!("{}_{}", msg.meta.name, self.tools.len()));
+        let id = ToolId::from(format!("{}_{}", msg.meta.name, self.tools_added));
+        self.tools_added += 1;
         let meta = ToolMetaWithId {
             id: id.clone(),
             meta: msg.meta,
@@ -180,6 +223,19 @@ impl OnRequest<AddTool> for ReasoningRouter {
     }
 }
 
+pub struct RemoveTool {
+    id: ToolId,
+}
+
+#[async_trait]
+impl OnEvent<RemoveTool> for ReasoningRouter {
+    async fn handle(&mut self, msg: RemoveTool, _ctx: &mut Context<Self>) -> Result<()> {
+        self.tools.remove(&msg.id);
+        log::info!("Tool removed: {}", msg.id);
+        Ok(())
+    }
+}
+
 pub struct ToolRequest {
     pub value: Value,
 }
@@ -204,6 +260,29 @@ where
     }
 }
 
+struct GetToolLinks {
+    ids: Vec<ToolId>,
+}
+
+impl Request for GetToolLinks {
+    type Response = Vec<Option<ToolLink>>;
+}
+
+#[async_trait]
+impl OnRequest<GetToolLinks> for ReasoningRouter {
+    async fn on_request(
+        &mut self,
+        msg: GetToolLinks,
+        _ctx: &mut Context<Self>,
+    ) -> Result<Vec<Option<ToolLink>>> {
+        Ok(msg
+            .ids
+            .iter()
+            .map(|id| self.tools.get(id).map(|record| record.link.clone()))
+            .collect())
+    }
+}
+
 struct GetTools;
 
 impl Request for GetTools {
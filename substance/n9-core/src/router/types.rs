@@ -1,15 +1,54 @@
-use crate::router::tool::ToolInfo;
+use crate::router::tool::{ToolId, ToolInfo};
 use crb::superagent::Request;
+use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Role {
     Developer,
     User,
     Assistant,
+    /// The result of a tool call
+    Tool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub role: Role,
     pub content: String,
+    /// Tools that the assistant calls, there could be several calls at once
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+    /// The call answered by a tool message
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+}
+
+impl Message {
+    pub fn new(role: Role, content: impl Into<String>) -> Self {
+        Self {
+            role,
+            content: content.into(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+
+    pub fn tool_result(call_id: String, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(call_id),
+            ..Self::new(Role::Tool, content)
+        }
+    }
+}
+
+/// A call of a tool requested by a model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// The id assigned by the model to match the result
+    pub id: String,
+    pub tool: ToolId,
+    /// JSON of parameters as it was generated by the model
+    pub arguments: String,
 }
 
 #[derive(Default)]
@@ -28,10 +67,7 @@ impl ChatRequest {
 
 impl ChatRequest {
     pub fn user(text: &str) -> Self {
-        let message = Message {
-            role: Role::User,
-            content: text.to_string(),
-        };
+        let message = Message::new(Role::User, text);
         Self {
             messages: vec![message],
         }
@@ -57,7 +93,7 @@ impl ChatResponse {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct ToolingChatRequest {
     pub messages: Vec<Message>,
     pub tools: Vec<ToolInfo>,
@@ -91,6 +127,14 @@ impl ToolingChatResponse {
         text
     }
 
+    /// Calls of tools requested in all messages of the response.
+    pub fn tool_calls(&self) -> Vec<ToolCall> {
+        self.messages
+            .iter()
+            .flat_map(|msg| msg.tool_calls.iter().cloned())
+            .collect()
+    }
+
     pub fn without_tools(self) -> ChatResponse {
         ChatResponse {
             messages: self.messages,
@@ -0,0 +1,221 @@
+//! Deserialization of configs with values of the environment layer.
+//!
+//! Variables are always strings, so they are parsed when the target field
+//! is a boolean or a number. Strings of other fields are kept as they are.
+//! Contents of internally tagged and untagged enums are buffered by serde
+//! and are not coerced.
+
+use serde::de::value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer};
+use serde::de::{DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use toml::de::Error;
+use toml::Value;
+
+/// Deserializes the config parsing strings by types of target fields.
+pub fn from_config<C: DeserializeOwned>(value: Value) -> Result<C, Error> {
+    C::deserialize(Coerce(value))
+}
+
+/// The value that is coerced into the requested type if it's a string.
+pub struct Coerce(pub Value);
+
+impl<'de> IntoDeserializer<'de, Error> for Coerce {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+fn entries<'de>(
+    table: toml::Table,
+) -> MapDeserializer<'de, impl Iterator<Item = (String, Coerce)>, Error> {
+    MapDeserializer::new(table.into_iter().map(|(key, value)| (key, Coerce(value))))
+}
+
+fn items(array: Vec<Value>) -> SeqDeserializer<impl Iterator<Item = Coerce>, Error> {
+    SeqDeserializer::new(array.into_iter().map(Coerce))
+}
+
+macro_rules! parse {
+    ($($method:ident => $visit:ident($ty:ty),)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0 {
+                    Value::String(raw) => match raw.trim().parse::<$ty>() {
+                        Ok(value) => visitor.$visit(value),
+                        // Reports the type mismatch of the original value
+                        Err(_) => Value::String(raw).$method(visitor),
+                    },
+                    value => value.$method(visitor),
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! delegate {
+    ($($method:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.0.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Coerce {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Table(table) => visitor.visit_map(entries(table)),
+            Value::Array(array) => visitor.visit_seq(items(array)),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    parse! {
+        deserialize_bool => visit_bool(bool),
+        deserialize_i8 => visit_i64(i64),
+        deserialize_i16 => visit_i64(i64),
+        deserialize_i32 => visit_i64(i64),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_u8 => visit_u64(u64),
+        deserialize_u16 => visit_u64(u64),
+        deserialize_u32 => visit_u64(u64),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_f32 => visit_f64(f64),
+        deserialize_f64 => visit_f64(f64),
+    }
+
+    delegate! {
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_unit,
+        deserialize_identifier,
+        deserialize_ignored_any,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        // TOML has no nulls, a missing field is `None`
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.0.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            // A unit variant
+            Value::String(variant) => visitor.visit_enum(variant.into_deserializer()),
+            // A variant with its content, e.g. `{ interval = "60" }`
+            Value::Table(table) if table.len() == 1 => {
+                visitor.visit_enum(MapAccessDeserializer::new(entries(table)))
+            }
+            value => value.deserialize_enum(name, variants, visitor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    enum Schedule {
+        Interval(u64),
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Segment {
+        api_key: String,
+        max_tokens: u32,
+        temperature: Option<f32>,
+        stream: bool,
+        schedule: Schedule,
+        stop: Vec<String>,
+    }
+
+    #[test]
+    fn test_coerce_strings() {
+        let value: Value = toml::from_str(
+            r#"
+            api_key = "123"
+            max_tokens = "512"
+            temperature = "0.5"
+            stream = "true"
+            schedule = { interval = "60" }
+            stop = ["1"]
+            "#,
+        )
+        .unwrap();
+        let segment: Segment = from_config(value).unwrap();
+        // Strings are kept for string fields even if they look like numbers
+        assert_eq!(segment.api_key, "123");
+        assert_eq!(segment.max_tokens, 512);
+        assert_eq!(segment.temperature, Some(0.5));
+        assert!(segment.stream);
+        assert_eq!(segment.schedule, Schedule::Interval(60));
+        assert_eq!(segment.stop, ["1"]);
+
+        let value: Value = toml::from_str("max_tokens = \"many\"").unwrap();
+        assert!(from_config::<Segment>(value).is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use derive_more::{Deref, DerefMut, From, Into};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use toml::{Table, Value};
+use ui9::names::Fqn;
+use ui9_dui::{Flow, Listener, Publisher, Subscriber, Tracer, Unified};
+
+/// The placeholder of secret values in published configs.
+pub const REDACTED: &str = "<redacted>";
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct ConfigurationSub {
+    listener: Listener<Configuration>,
+}
+
+impl Subscriber for Configuration {
+    type Driver = ConfigurationSub;
+}
+
+impl ConfigurationSub {
+    /// Sets the value of the key in the layer file.
+    pub fn set(&mut self, layer: String, key: Vec<String>, value: Value) {
+        let action = ConfigurationAction::Set { layer, key, value };
+        self.listener.action(action);
+    }
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct ConfigurationPub {
+    tracer: Tracer<Configuration>,
+}
+
+impl Publisher for Configuration {
+    type Driver = ConfigurationPub;
+}
+
+impl ConfigurationPub {
+    pub fn layers(&mut self, layers: Vec<String>) {
+        let event = ConfigurationEvent::Layers { layers };
+        self.tracer.event(event);
+    }
+
+    pub fn merged(&mut self, config: Table, origins: BTreeMap<String, String>) {
+        let event = ConfigurationEvent::Merged { config, origins };
+        self.tracer.event(event);
+    }
+}
+
+impl Unified for Configuration {
+    fn fqn() -> Fqn {
+        Fqn::root("@config")
+    }
+}
+
+/// The merged configuration with redacted secrets and the layers it consists of.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Configuration {
+    /// Sources of layers in the order of merging
+    pub layers: Vec<String>,
+    pub config: Table,
+    /// Sources of effective values by their keys
+    pub origins: BTreeMap<String, String>,
+}
+
+impl Flow for Configuration {
+    type Event = ConfigurationEvent;
+    type Action = ConfigurationAction;
+
+    fn apply(&mut self, event: Self::Event) {
+        match event {
+            ConfigurationEvent::Layers { layers } => {
+                self.layers = layers;
+            }
+            ConfigurationEvent::Merged { config, origins } => {
+                self.config = config;
+                self.origins = origins;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ConfigurationEvent {
+    Layers {
+        layers: Vec<String>,
+    },
+    Merged {
+        config: Table,
+        origins: BTreeMap<String, String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ConfigurationAction {
+    /// Writes the value to the layer. The change is applied by reloading the layer.
+    Set {
+        layer: String,
+        key: Vec<String>,
+        value: Value,
+    },
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct SharedConfigSub {
+    listener: Listener<SharedConfig>,
+}
+
+impl Subscriber for SharedConfig {
+    type Driver = SharedConfigSub;
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct SharedConfigPub {
+    tracer: Tracer<SharedConfig>,
+}
+
+impl Publisher for SharedConfig {
+    type Driver = SharedConfigPub;
+}
+
+impl SharedConfigPub {
+    pub fn set(&mut self, config: Table) {
+        let event = SharedConfigEvent::Set { config };
+        self.tracer.event(event);
+    }
+}
+
+impl Unified for SharedConfig {
+    fn fqn() -> Fqn {
+        Fqn::root("@shared-config")
+    }
+}
+
+/// A config fragment that a node shares with mesh peers.
+/// Peers that trust the node use it as a layer below their files.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SharedConfig {
+    pub config: Table,
+}
+
+impl Flow for SharedConfig {
+    type Event = SharedConfigEvent;
+    type Action = ();
+
+    fn apply(&mut self, event: Self::Event) {
+        match event {
+            SharedConfigEvent::Set { config } => {
+                self.config = config;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SharedConfigEvent {
+    Set { config: Table },
+}
@@ -1,43 +1,222 @@
-use anyhow::{anyhow, Result};
+use crate::config_coerce::from_config;
+use crate::config_flow::{
+    Configuration, ConfigurationAction, SharedConfig, SharedConfigEvent, REDACTED,
+};
+use crate::config_profile::{MergedConfig, PROFILES_KEY};
+use crate::config_schema::{document_template, json_schema, KeyDoc};
+use crate::config_stack::{ConfigStack, LayerSpec, SegmentSecrets};
+use crate::diagnostics::{key_path, locate, ConfigDiagnostics, Diagnostic, SegmentError};
+use anyhow::{anyhow, Error, Result};
 use async_trait::async_trait;
 use crb::agent::{Address, Agent, Context, DoAsync, ManagedContext, Next, OnEvent, ToAddress};
 use crb::core::Unique;
 use crb::send::{Recipient, Sender};
-use crb::superagent::{ManageSubscription, StreamSession, Subscription, Timeout, Timer};
+use crb::superagent::{Drainer, ManageSubscription, StreamSession, Subscription, Timeout, Timer};
 use derive_more::{Deref, DerefMut, From};
 use notify::{
     recommended_watcher, Event, EventHandler, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
 };
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::fs;
+use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
 use toml::{Table, Value};
-use ui9_dui::Operation;
+use toml_edit::DocumentMut;
+use ui9_codec::{Codec, Json, Yaml};
+use ui9_dui::reporter::Reporter;
+use ui9_dui::{Act, Operation, Pub, Sub, SubEvent, Unified};
+use ui9_net::access;
+use ui9_net::service::AllowedPeers;
+use ui9_net::tracers::peer::PeerId;
+use ui9_net::RemoteUnifiedExt;
 
-const CONFIG_NAME: &str = "nine.toml";
-const TEMPLATE_NAME: &str = "nine.example.toml";
+pub const SCHEMA_NAME: &str = "nine.schema.json";
+const DOTENV_NAME: &str = ".env";
+const ENV_PREFIX: &str = "NINE__";
+const ENV_SEPARATOR: &str = "__";
+const INCLUDE_KEY: &str = "include";
+const PARTICLES_KEY: &str = "particle";
+const SHARE_KEY: &str = "share";
+const SHARE_PEERS_KEY: &str = "share_peers";
+const CONFIG_WRITERS_KEY: &str = "config_writers";
+const ENV_SOURCE: &str = "environment";
 
 pub struct ConfigLayer {
     path: Arc<PathBuf>,
+    /// The last valid config of the layer
     config: Value,
-    _watcher: RecommendedWatcher,
+    /// The content the config was parsed from
+    content: String,
 }
 
 impl ConfigLayer {
-    async fn read_config(&mut self) -> Result<()> {
-        let op = Operation::start(&format!("Reading configuration: {}", self.path.display()));
+    /// Reads the layer. The previous config is kept if the new one is invalid.
+    async fn read_config(&mut self) -> Result<(), Diagnostic> {
+        let mut op = Operation::start(&format!("Reading configuration: {}", self.path.display()));
         log::info!("Reading the config layer: {}", self.path.display());
-        let content = fs::read_to_string(self.path.as_ref()).await?;
-        let config = toml::from_str(&content)?;
+        match self.parse_config().await {
+            Ok(()) => {
+                op.end(&format!(
+                    "Complete reading the config: {}",
+                    self.path.display()
+                ));
+                Ok(())
+            }
+            Err(diagnostic) => {
+                op.failure(&diagnostic.to_string());
+                op.end(&format!("Invalid config layer: {}", self.path.display()));
+                Err(diagnostic)
+            }
+        }
+    }
+
+    async fn parse_config(&mut self) -> Result<(), Diagnostic> {
+        let file = self.source();
+        let content = match fs::read_to_string(self.path.as_ref()).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                log::warn!("The config layer is missing and considered empty: {file}");
+                String::new()
+            }
+            Err(err) => {
+                return Err(Diagnostic::new(err).in_file(&file));
+            }
+        };
+        let config = LayerFormat::of(&self.path)
+            .parse(&content)
+            .map_err(|diagnostic| diagnostic.in_file(&file))?;
+        if let Some(include) = config.get(INCLUDE_KEY) {
+            let valid = include
+                .as_array()
+                .map_or(false, |paths| paths.iter().all(Value::is_str));
+            if !valid {
+                let key = [INCLUDE_KEY.to_string()];
+                let diagnostic = Diagnostic::new("Must be an array of paths")
+                    .in_file(&file)
+                    .with_key(&key);
+                return Err(match locate(&content, &key) {
+                    Some((_, span)) => diagnostic.at(&content, span.start),
+                    None => diagnostic,
+                });
+            }
+        }
         self.config = config;
-        op.end(&format!(
-            "Complete reading the config: {}",
-            self.path.display()
-        ));
+        self.content = content;
         Ok(())
     }
+
+    fn source(&self) -> String {
+        self.path.display().to_string()
+    }
+
+    /// Files included by the layer. Relative paths are resolved from the layer's directory.
+    fn includes(&self) -> Vec<PathBuf> {
+        let base = self.path.parent().unwrap_or(Path::new(""));
+        self.config
+            .get(INCLUDE_KEY)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(|include| base.join(include))
+            .collect()
+    }
+
+    /// The config without directives.
+    fn values(&self) -> Value {
+        let mut config = self.config.clone();
+        if let Value::Table(table) = &mut config {
+            table.remove(INCLUDE_KEY);
+        }
+        config
+    }
+}
+
+/// The format of a layer chosen by the extension. TOML is used by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LayerFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl LayerFormat {
+    fn of(path: &Path) -> Self {
+        match path.extension().and_then(OsStr::to_str) {
+            Some("json") => Self::Json,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Toml,
+        }
+    }
+
+    /// Converts the content into the common value tree.
+    fn parse(self, content: &str) -> Result<Value, Diagnostic> {
+        if content.trim().is_empty() {
+            return Ok(table());
+        }
+        let config: Value = match self {
+            Self::Toml => toml::from_str(content).map_err(|err| {
+                let diagnostic = Diagnostic::new(err.message());
+                match err.span() {
+                    Some(span) => diagnostic.at(content, span.start),
+                    None => diagnostic,
+                }
+            })?,
+            Self::Json => {
+                Json::<Value>::decode(content.as_bytes()).map_err(|err| match err
+                    .downcast_ref::<serde_json::Error>()
+                {
+                    Some(err) if err.line() > 0 => {
+                        Diagnostic::new(format!("Invalid JSON: {}", strip_location(err)))
+                            .at_position(err.line(), err.column())
+                    }
+                    _ => Diagnostic::new(format!("Invalid JSON: {err}")),
+                })?
+            }
+            Self::Yaml => Yaml::<Value>::decode(content.as_bytes()).map_err(|err| {
+                let location = err
+                    .downcast_ref::<serde_yaml::Error>()
+                    .and_then(|err| Some((err, err.location()?)));
+                match location {
+                    Some((err, location)) => {
+                        Diagnostic::new(format!("Invalid YAML: {}", strip_location(err)))
+                            .at_position(location.line(), location.column())
+                    }
+                    None => Diagnostic::new(format!("Invalid YAML: {err}")),
+                }
+            })?,
+        };
+        if !config.is_table() {
+            return Err(Diagnostic::new("The layer must contain a table of keys"));
+        }
+        Ok(config)
+    }
+}
+
+/// Removes ` at line X column Y` from the message, since it's in the diagnostic.
+fn strip_location(err: &impl ToString) -> String {
+    let message = err.to_string();
+    match message.rsplit_once(" at line ") {
+        Some((message, _)) => message.to_string(),
+        None => message,
+    }
+}
+
+/// A config fragment shared by a trusted peer.
+struct MeshLayer {
+    peer: PeerId,
+    config: Value,
+    _shared: Sub<SharedConfig>,
+}
+
+impl MeshLayer {
+    fn source(&self) -> String {
+        format!("mesh:{}", self.peer)
+    }
 }
 
 pub struct ChangedFiles {
@@ -55,20 +234,109 @@ impl ChangedFiles {
 }
 
 pub struct ConfigLoader {
-    layers: Vec<ConfigLayer>,
+    stack: ConfigStack,
+    layers: HashMap<Arc<PathBuf>, ConfigLayer>,
+    /// Expanded layers in the order of merging
+    order: Vec<Arc<PathBuf>>,
+    /// Watchers of directories that contain layers
+    watchers: HashMap<Arc<PathBuf>, RecommendedWatcher>,
     changed_files: Option<ChangedFiles>,
     subscribers: HashSet<Unique<ConfigUpdates>>,
+    /// The merged config with the overlay of the active profile
     merged_config: Value,
+    profile: Option<String>,
+    /// The environment layer that overrides all files
+    env_config: Value,
+    /// Layers shared by peers in the order of the stack
+    mesh: Vec<MeshLayer>,
+    /// The fragment shared with allowed peers
+    shared: Pub<SharedConfig>,
+    /// The `share` section of the config before redaction
+    shared_config: Value,
+    /// Sources of all layers in the order of merging
+    sources: Vec<String>,
+    /// Sources of effective values
+    origins: BTreeMap<String, String>,
+    diagnostics: Pub<ConfigDiagnostics>,
+    problems: BTreeMap<String, Vec<Diagnostic>>,
+    configuration: Pub<Configuration>,
+    /// Secret fields of segments known by the stack and registered by consumers.
+    /// Segments that are not listed here are not published at all.
+    secrets: SegmentSecrets,
+    /// Peers allowed to change layers with the `@config` flow
+    writers: AllowedPeers,
 }
 
 impl ConfigLoader {
-    pub fn new() -> Self {
+    pub fn new(stack: ConfigStack) -> Self {
         Self {
-            layers: Vec::new(),
+            secrets: stack.secrets.clone(),
+            stack,
+            layers: HashMap::new(),
+            order: Vec::new(),
+            watchers: HashMap::new(),
             changed_files: None,
             subscribers: HashSet::new(),
             merged_config: table(),
+            profile: None,
+            env_config: table(),
+            mesh: Vec::new(),
+            shared: Pub::unified(),
+            shared_config: table(),
+            sources: Vec::new(),
+            origins: BTreeMap::new(),
+            diagnostics: Pub::unified(),
+            problems: BTreeMap::new(),
+            configuration: Pub::unified(),
+            writers: AllowedPeers::default(),
+        }
+    }
+
+    /// Reads and merges layers once without watching them, e.g. for command line tools.
+    /// Mesh layers are skipped, since they are received from the network.
+    pub async fn load(mut stack: ConfigStack) -> Self {
+        // Tools never create missing layers
+        for spec in &mut stack.layers {
+            if let LayerSpec::File { create, .. } = spec {
+                *create = false;
+            }
         }
+        let mut loader = Self::new(stack);
+        loader.read_env();
+        let (order, _) = loader.expand_stack().await;
+        loader.order = order;
+        let merged = loader.merge_layers();
+        loader.merged_config = merged.config;
+        loader.profile = merged.profile;
+        loader.origins = merged.origins;
+        loader.sources = merged.sources;
+        loader.writers = loader.allowed_peers(CONFIG_WRITERS_KEY, merged.config_writers);
+        loader
+    }
+
+    /// The merged config of all layers with the overlay of the active profile.
+    pub fn merged_config(&self) -> &Value {
+        &self.merged_config
+    }
+
+    /// The profile overlaid on top of the config.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Sources of effective values by their keys.
+    pub fn origins(&self) -> &BTreeMap<String, String> {
+        &self.origins
+    }
+
+    /// Sources of layers in the order of merging.
+    pub fn sources(&self) -> &[String] {
+        &self.sources
+    }
+
+    /// Problems of layers grouped by their sources.
+    pub fn problems(&self) -> &BTreeMap<String, Vec<Diagnostic>> {
+        &self.problems
     }
 }
 
@@ -86,61 +354,350 @@ impl Agent for ConfigLoader {
 }
 
 impl ConfigLoader {
-    async fn add_layer(&mut self, path: PathBuf, ctx: &mut Context<Self>) -> Result<()> {
+    fn watch(&self, path: &Arc<PathBuf>, ctx: &mut Context<Self>) -> Result<RecommendedWatcher> {
+        let forwarder = EventsForwarder::new(ctx, path.clone());
+        watch_dir(path, forwarder)
+    }
+
+    /// Adds and reads a layer if it's not loaded yet.
+    async fn add_layer(&mut self, path: Arc<PathBuf>, create: bool) {
+        if self.layers.contains_key(&path) {
+            return;
+        }
         log::info!("Add a config layer: {}", path.display());
-        let path = Arc::new(path);
+        let source = path.display().to_string();
+        let mut problems = Vec::new();
 
-        // Create a config file if doesn't exist
-        if !path.exists() {
+        // Create a config file if doesn't exist and it's allowed
+        if create && !path.exists() {
             log::info!("Creating an empty config layer: {}", path.display());
-            fs::write(path.as_ref(), "").await?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).await.ok();
+            }
+            if let Err(err) = fs::write(path.as_ref(), "").await {
+                problems.push(Diagnostic::new(err).in_file(&source));
+            }
         }
 
-        // Setup a watcher for file
-        let forwarder = EventsForwarder::new(ctx, path.clone());
-        let mut watcher = recommended_watcher(forwarder)?;
-        watcher.watch(path.as_ref(), RecursiveMode::NonRecursive)?;
-
         // Read a config
         let mut layer = ConfigLayer {
-            path,
+            path: path.clone(),
             config: table(),
-            _watcher: watcher,
+            content: String::new(),
         };
-        layer.read_config().await?;
+        if let Err(diagnostic) = layer.read_config().await {
+            problems.push(diagnostic);
+        }
+        self.set_problems(source, problems);
+        self.layers.insert(path, layer);
+    }
 
-        self.layers.push(layer);
-        Ok(())
+    /// Watches directories of layers instead of files, since editors replace
+    /// files on saving and a watch of the replaced file never fires again.
+    fn sync_watchers(&mut self, dirs: HashSet<Arc<PathBuf>>, ctx: &mut Context<Self>) {
+        // Watches of removed directories are re-established when they appear again
+        self.watchers
+            .retain(|dir, _| dirs.contains(dir) && dir.is_dir());
+        for dir in dirs {
+            if !self.watchers.contains_key(&dir) && dir.is_dir() {
+                match self.watch(&dir, ctx) {
+                    Ok(watcher) => {
+                        self.watchers.insert(dir, watcher);
+                    }
+                    Err(err) => {
+                        log::error!("Can't watch the config dir {}: {err}", dir.display());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Expands the stack into layers: directories are listed
+    /// and included files are placed before the including layer.
+    /// Returns layers in the order of merging and directories that contain them.
+    async fn expand_stack(&mut self) -> (Vec<Arc<PathBuf>>, HashSet<Arc<PathBuf>>) {
+        let mut roots = Vec::new();
+        let mut dirs = HashSet::new();
+        for spec in self.stack.layers.clone() {
+            match spec {
+                LayerSpec::File { path, create } => {
+                    roots.push((path, create));
+                }
+                LayerSpec::Directory { path } => {
+                    dirs.insert(Arc::new(path.clone()));
+                    for file in config_files(&path).await {
+                        roots.push((file, false));
+                    }
+                }
+                LayerSpec::Mesh { .. } => {
+                    // Subscribed on start
+                }
+            }
+        }
+
+        let mut order = Vec::new();
+        let mut visited = HashSet::new();
+        for (path, create) in roots {
+            let mut steps = vec![Step::Visit(Arc::new(path), create)];
+            while let Some(step) = steps.pop() {
+                match step {
+                    Step::Visit(path, create) => {
+                        // Merged already or included recursively
+                        if !visited.insert(path.clone()) {
+                            continue;
+                        }
+                        dirs.insert(Arc::new(parent_dir(&path)));
+                        self.add_layer(path.clone(), create).await;
+                        steps.push(Step::Emit(path.clone()));
+                        if let Some(layer) = self.layers.get(&path) {
+                            for include in layer.includes().into_iter().rev() {
+                                steps.push(Step::Visit(Arc::new(include), false));
+                            }
+                        }
+                    }
+                    Step::Emit(path) => {
+                        order.push(path);
+                    }
+                }
+            }
+        }
+
+        // Drop layers that are not used anymore
+        let unused: Vec<_> = self
+            .layers
+            .keys()
+            .filter(|path| !visited.contains(*path))
+            .cloned()
+            .collect();
+        for path in unused {
+            if let Some(layer) = self.layers.remove(&path) {
+                log::info!("Remove the config layer: {}", path.display());
+                self.set_problems(layer.source(), Vec::new());
+            }
+        }
+        (order, dirs)
     }
 
     /// Updates and merges config files
-    async fn update_configs(&mut self) -> Result<()> {
+    async fn update_configs(&mut self, ctx: &mut Context<Self>) -> Result<()> {
         let changed_files = self
             .changed_files
             .take()
             .map(|record| record.files)
             .unwrap_or_default();
-        let mut new_merged_config = table();
-        for layer in &mut self.layers {
-            if changed_files.contains(&layer.path) {
-                layer.read_config().await?;
+        for path in &changed_files {
+            let Some(layer) = self.layers.get_mut(path) else {
+                continue;
+            };
+            let result = layer.read_config().await;
+            let source = layer.source();
+            self.set_problems(source, result.err().into_iter().collect());
+        }
+
+        // Includes and directories could be changed
+        let (order, dirs) = self.expand_stack().await;
+        self.order = order;
+        self.sync_watchers(dirs, ctx);
+
+        let merged = self.merge_layers();
+        let share_peers = self.allowed_peers(SHARE_PEERS_KEY, merged.share_peers);
+        access::restrict(SharedConfig::fqn(), share_peers);
+        self.writers = self.allowed_peers(CONFIG_WRITERS_KEY, merged.config_writers);
+        access::restrict(Configuration::fqn(), self.writers.clone());
+        self.share_config(merged.shared);
+        self.origins = merged.origins;
+
+        if self.profile != merged.profile {
+            match &merged.profile {
+                Some(name) => log::info!("Active config profile: {name}"),
+                None => log::info!("No config profile is active"),
             }
-            merge_configs(&mut new_merged_config, &layer.config);
+            self.profile = merged.profile;
         }
-        if self.merged_config != new_merged_config {
-            let new_config = NewConfig(new_merged_config.clone());
+
+        if self.sources != merged.sources {
+            self.configuration.layers(merged.sources.clone());
+            self.sources = merged.sources;
+        }
+
+        if self.merged_config != merged.config {
+            let new_config = NewConfig(merged.config.clone());
             for subscriber in &self.subscribers {
                 subscriber.send(new_config.clone()).ok();
             }
-            self.merged_config = new_merged_config;
+            self.merged_config = merged.config;
+            self.publish_config();
         }
         Ok(())
     }
 
-    fn schedule_update(&mut self, path: Arc<PathBuf>, ctx: &mut Context<Self>) -> Result<()> {
+    /// Merges all layers in the order of priority and overlays the active profile.
+    fn merge_layers(&self) -> MergedLayers {
+        // Mesh layers have the lowest priority and the environment the highest
+        let mut sources = Vec::new();
+        for layer in &self.mesh {
+            sources.push((layer.source(), layer.config.clone()));
+        }
+        for path in &self.order {
+            if let Some(layer) = self.layers.get(path) {
+                sources.push((layer.source(), layer.values()));
+            }
+        }
+        sources.push((ENV_SOURCE.to_string(), self.env_config.clone()));
+
+        let mut config = table();
+        let mut origins = BTreeMap::new();
+        for (source, values) in &sources {
+            merge_configs(&mut config, values);
+            track_origins(&mut origins, values, source, &mut Vec::new());
+        }
+        let shared = match &mut config {
+            Value::Table(table) => table.remove(SHARE_KEY),
+            _ => None,
+        };
+        let merged = MergedConfig::with_profile(config, self.stack.profile.as_deref());
+        let mut config = merged.value;
+        // Lists of peers could be set by profiles as well
+        let (share_peers, config_writers) = match &mut config {
+            Value::Table(table) => (
+                table.remove(SHARE_PEERS_KEY),
+                table.remove(CONFIG_WRITERS_KEY),
+            ),
+            _ => (None, None),
+        };
+        // Values of the profile come from its section
+        if let Some(name) = &merged.profile {
+            let prefix = [PROFILES_KEY.to_string(), name.clone()];
+            let overlaid: Vec<_> = origins
+                .iter()
+                .filter_map(|(key, source)| {
+                    let key = key.strip_prefix(prefix.as_slice())?;
+                    Some((key.to_vec(), source.clone()))
+                })
+                .collect();
+            origins.extend(overlaid);
+        }
+        let origins = origins
+            .into_iter()
+            .filter(|(key, _)| lookup(&config, key).map_or(false, |v| !v.is_table()))
+            .map(|(key, source)| (key_path(&key), source))
+            .collect();
+        MergedLayers {
+            config,
+            profile: merged.profile,
+            shared: shared.unwrap_or_else(table),
+            share_peers,
+            config_writers,
+            origins,
+            sources: sources.into_iter().map(|(source, _)| source).collect(),
+        }
+    }
+
+    /// Publishes the merged config with redacted secrets.
+    fn publish_config(&mut self) {
+        let config = self.redacted(self.merged_config.clone());
+        if let Value::Table(table) = config {
+            self.configuration.merged(table, self.origins.clone());
+        }
+    }
+
+    /// Publishes the `share` section of the config if it was changed.
+    fn share_config(&mut self, config: Value) {
+        if self.shared_config != config {
+            self.shared_config = config;
+            self.publish_shared();
+        }
+    }
+
+    /// Publishes the `share` section for peers, values of secret fields are never shared.
+    fn publish_shared(&mut self) {
+        let config = self.redacted(self.shared_config.clone());
+        if let Value::Table(table) = config {
+            self.shared.set(table);
+        }
+    }
+
+    /// Redacts secret fields of the config. Segments of particles that have not
+    /// declared their secret fields are withheld, since any field could be a secret.
+    fn redacted(&self, mut config: Value) -> Value {
+        if let Some(Value::Table(particles)) = config.get_mut(PARTICLES_KEY) {
+            particles.retain(|namespace, _| self.secrets.contains_key(namespace));
+        }
+        let keys: Vec<_> = self.secrets.values().flatten().cloned().collect();
+        redact_keys(&mut config, &keys);
+        config
+    }
+
+    /// Parses the list of peers of the key, e.g. `share_peers` that could subscribe
+    /// to the `share` section. No peers are allowed if the list is missing or invalid.
+    fn allowed_peers(&mut self, key: &str, peers: Option<Value>) -> AllowedPeers {
+        let parsed = match peers {
+            Some(peers) => from_config::<Vec<String>>(peers)
+                .map_err(Error::from)
+                .and_then(|peers| AllowedPeers::parse(&peers)),
+            None => Ok(AllowedPeers::default()),
+        };
+        let mut problems = Vec::new();
+        let allowed = parsed.unwrap_or_else(|err| {
+            problems.push(self.locate(&[key.to_string()], err.to_string()));
+            AllowedPeers::default()
+        });
+        self.set_problems(key.into(), problems);
+        allowed
+    }
+
+    /// Subscribes to the config shared by the peer.
+    fn add_mesh_layer(&mut self, peer: PeerId, ctx: &mut Context<Self>) -> Result<()> {
+        log::info!("Add a mesh config layer of {peer}");
+        let mut shared: Sub<SharedConfig> = Sub::remote_unified(peer);
+        let stream = UnboundedReceiverStream::new(shared.receiver()?)
+            .map(move |event| MeshEvent { peer, event });
+        ctx.consume(Drainer::new(stream));
+        let layer = MeshLayer {
+            peer,
+            config: table(),
+            _shared: shared,
+        };
+        self.mesh.push(layer);
+        Ok(())
+    }
+
+    /// Writes the value to the layer file keeping the formatting.
+    /// The watcher reloads the layer after that.
+    /// Remote peers could write only if they are listed in `config_writers`.
+    async fn write_value(
+        &self,
+        origin: Option<&str>,
+        layer: &str,
+        key: &[String],
+        value: &Value,
+    ) -> Result<()> {
+        if let Some(peer) = origin {
+            let peer: PeerId = peer
+                .parse()
+                .map_err(|err| anyhow!("Invalid peer id {peer}: {err}"))?;
+            self.writers.check(&peer)?;
+        }
+        let path = self
+            .order
+            .iter()
+            .find(|path| path.display().to_string() == layer)
+            .ok_or_else(|| anyhow!("The config layer {layer} is not loaded"))?;
+        if LayerFormat::of(path) != LayerFormat::Toml {
+            return Err(anyhow!("Only TOML layers could be changed"));
+        }
+        let content = fs::read_to_string(path.as_ref()).await?;
+        let content = set_value(&content, key, value)?;
+        fs::write(path.as_ref(), content).await?;
+        Ok(())
+    }
+
+    /// Schedules rereading of changed layers. The stack is expanded again
+    /// even without changed layers, since files could be added to directories.
+    fn schedule_update(&mut self, paths: Vec<Arc<PathBuf>>, ctx: &mut Context<Self>) -> Result<()> {
         match self.changed_files.as_mut() {
             Some(changed_files) => {
-                changed_files.files.insert(path);
+                changed_files.files.extend(paths);
             }
             None => {
                 let mut timeout = Timer::new();
@@ -148,16 +705,112 @@ impl ConfigLoader {
                 timeout.schedule(duration)?;
                 ctx.consume(timeout.events()?);
                 let mut changed_files = ChangedFiles::new(timeout);
-                changed_files.files.insert(path);
+                changed_files.files.extend(paths);
                 self.changed_files = Some(changed_files);
             }
         }
         Ok(())
     }
 
+    /// Reads variables of the `.env` file and the environment.
+    fn read_env(&mut self) {
+        let mut problems = Vec::new();
+        let mut vars = Vec::new();
+        match dotenvy::from_filename_iter(DOTENV_NAME) {
+            Ok(iter) => {
+                for item in iter {
+                    match item {
+                        Ok(pair) => vars.push(pair),
+                        Err(err) => problems.push(Diagnostic::new(err).in_file(DOTENV_NAME)),
+                    }
+                }
+            }
+            Err(err) if err.not_found() => {}
+            Err(err) => problems.push(Diagnostic::new(err).in_file(DOTENV_NAME)),
+        }
+        // The environment overrides the `.env` file
+        let env = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+        vars.extend(env);
+
+        let mut config = table();
+        for (name, raw) in vars {
+            if let Some(value) = env_value(&name, &raw) {
+                merge_configs(&mut config, &value);
+            }
+        }
+        self.env_config = config;
+        self.set_problems(DOTENV_NAME.into(), problems);
+    }
+
     fn current_config(&self) -> Value {
         self.merged_config.clone()
     }
+
+    /// Publishes problems of the source if they were changed.
+    fn set_problems(&mut self, source: String, diagnostics: Vec<Diagnostic>) {
+        let current = self.problems.get(&source);
+        if current.map(Vec::as_slice).unwrap_or_default() == diagnostics.as_slice() {
+            return;
+        }
+        if diagnostics.is_empty() {
+            log::info!("Config problems resolved: {source}");
+            self.problems.remove(&source);
+            self.diagnostics.clear(source);
+        } else {
+            for diagnostic in &diagnostics {
+                let message = format!("Config problem: {diagnostic}");
+                log::error!("{message}");
+                Reporter::failure(&message);
+            }
+            self.problems.insert(source.clone(), diagnostics.clone());
+            self.diagnostics.set(source, diagnostics);
+        }
+    }
+
+    /// Finds the location of the key in layers. Layers with a deeper match win,
+    /// the latest layer wins among equal ones since it overrides others.
+    /// The section of the active profile wins over the base config.
+    pub fn locate(&self, key: &[String], message: String) -> Diagnostic {
+        let mut best = self.find_key(key);
+        if let Some(name) = &self.profile {
+            let mut profile_key = vec![PROFILES_KEY.to_string(), name.clone()];
+            profile_key.extend_from_slice(key);
+            let prefix = profile_key.len() - key.len();
+            // Only matches inside of the section of the profile are taken
+            let overlay = self
+                .find_key(&profile_key)
+                .filter(|(depth, _, _)| *depth > prefix)
+                .map(|(depth, layer, offset)| (depth - prefix, layer, offset));
+            if let Some(overlay) = overlay {
+                if best.map_or(true, |(depth, _, _)| overlay.0 >= depth) {
+                    best = Some(overlay);
+                }
+            }
+        }
+        let diagnostic = Diagnostic::new(message).with_key(key);
+        match best {
+            Some((_, layer, offset)) => diagnostic
+                .in_file(layer.source())
+                .at(&layer.content, offset),
+            None => diagnostic,
+        }
+    }
+
+    /// The deepest match of the key in layers and the offset of the match.
+    fn find_key(&self, key: &[String]) -> Option<(usize, &ConfigLayer, usize)> {
+        let mut best: Option<(usize, &ConfigLayer, usize)> = None;
+        let layers = self.order.iter().filter_map(|path| self.layers.get(path));
+        for layer in layers {
+            if let Some((depth, span)) = locate(&layer.content, key) {
+                if best.map_or(true, |(best_depth, _, _)| depth >= best_depth) {
+                    best = Some((depth, layer, span.start));
+                }
+            }
+        }
+        best
+    }
 }
 
 struct Initialize;
@@ -165,25 +818,125 @@ struct Initialize;
 #[async_trait]
 impl DoAsync<Initialize> for ConfigLoader {
     async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
-        // Global config layer: ~/.config/nine.toml
-        let config_dir = dirs::home_dir()
-            .ok_or_else(|| anyhow!("Config dir is not provided."))?
-            .join(".config")
-            .join("nine");
-        fs::create_dir_all(&config_dir).await?;
-        let global_config = config_dir.join(CONFIG_NAME);
-        self.add_layer(global_config, ctx).await?;
+        // Environment layer: NINE__PARTICLE__OPENAI__CONFIG__API_KEY
+        self.read_env();
 
-        // Local config layer: $PWD/nine.toml
-        let local_config = CONFIG_NAME.into();
-        self.add_layer(local_config, ctx).await?;
+        // Mesh layers are filled when peers send their configs
+        for spec in self.stack.layers.clone() {
+            if let LayerSpec::Mesh { peer } = spec {
+                self.add_mesh_layer(peer, ctx)?;
+            }
+        }
+
+        // Peers could subscribe to the fragment and change layers
+        // only if they are allowed by the config
+        access::restrict(SharedConfig::fqn(), AllowedPeers::default());
+        access::restrict(Configuration::fqn(), AllowedPeers::default());
 
-        self.update_configs().await?;
+        // File layers are expanded from the stack
+        self.update_configs(ctx).await?;
+
+        ctx.consume(self.configuration.actions()?);
 
         Ok(Next::events())
     }
 }
 
+enum Step {
+    Visit(Arc<PathBuf>, bool),
+    Emit(Arc<PathBuf>),
+}
+
+struct MergedLayers {
+    config: Value,
+    profile: Option<String>,
+    /// The `share` section removed from the config
+    shared: Value,
+    /// Peers allowed to subscribe to the `share` section
+    share_peers: Option<Value>,
+    /// Peers allowed to change layers
+    config_writers: Option<Value>,
+    origins: BTreeMap<String, String>,
+    sources: Vec<String>,
+}
+
+/// Records sources of values of the layer, later layers override earlier ones.
+/// Arrays are values as a whole, the same way as `merge_configs` does.
+fn track_origins(
+    origins: &mut BTreeMap<Vec<String>, String>,
+    value: &Value,
+    source: &str,
+    key: &mut Vec<String>,
+) {
+    match value {
+        Value::Table(table) => {
+            for (name, item) in table {
+                key.push(name.clone());
+                track_origins(origins, item, source, key);
+                key.pop();
+            }
+        }
+        _ => {
+            origins.insert(key.clone(), source.to_string());
+        }
+    }
+}
+
+fn lookup<'a>(value: &'a Value, key: &[String]) -> Option<&'a Value> {
+    key.iter()
+        .try_fold(value, |value, part| value.get(part.as_str()))
+}
+
+/// The directory of the file. It's the current directory for bare file names.
+fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Watches files of the directory without subdirectories.
+fn watch_dir(dir: &Path, handler: impl EventHandler) -> Result<RecommendedWatcher> {
+    let mut watcher = recommended_watcher(handler)?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// Layers of the directory touched by paths of an event.
+/// Names are compared, since paths of events could be absolute.
+fn affected_layers<'a>(
+    layers: impl IntoIterator<Item = &'a Arc<PathBuf>>,
+    dir: &Path,
+    paths: &[PathBuf],
+) -> Vec<Arc<PathBuf>> {
+    layers
+        .into_iter()
+        .filter(|layer| {
+            parent_dir(layer) == dir
+                && paths
+                    .iter()
+                    .any(|path| path.file_name() == layer.file_name())
+        })
+        .cloned()
+        .collect()
+}
+
+/// All `*.toml`, `*.json` and `*.yaml` files of the directory in lexical order.
+async fn config_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(mut entries) = fs::read_dir(dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let extension = path.extension().and_then(OsStr::to_str);
+            if matches!(extension, Some("toml" | "json" | "yaml" | "yml")) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
 #[derive(From)]
 struct EventsForwarder {
     tag: Arc<PathBuf>,
@@ -220,12 +973,18 @@ struct WatchEvent {
 impl OnEvent<WatchEvent> for ConfigLoader {
     async fn handle(&mut self, msg: WatchEvent, ctx: &mut Context<Self>) -> Result<()> {
         let event = msg.result?;
+        let dir = msg.tag;
         match event.kind {
-            EventKind::Create(_) | EventKind::Modify(_) => {
-                self.schedule_update(msg.tag, ctx)?;
-            }
-            _other => {
-                // TODO: How to handle other methods? What if the config was removed?
+            EventKind::Access(_) => {}
+            _ => {
+                // The directory itself was removed or renamed, the watch is stale
+                if event.paths.iter().any(|path| path == dir.as_ref()) {
+                    log::warn!("The config dir was moved: {}", dir.display());
+                    self.watchers.remove(&dir);
+                }
+                // Saving by renaming, removing and creating files is handled the same way
+                let layers = affected_layers(self.layers.keys(), &dir, &event.paths);
+                self.schedule_update(layers, ctx)?;
             }
         }
         Ok(())
@@ -234,8 +993,58 @@ impl OnEvent<WatchEvent> for ConfigLoader {
 
 #[async_trait]
 impl OnEvent<Timeout> for ConfigLoader {
-    async fn handle(&mut self, _: Timeout, _ctx: &mut Context<Self>) -> Result<()> {
-        self.update_configs().await
+    async fn handle(&mut self, _: Timeout, ctx: &mut Context<Self>) -> Result<()> {
+        self.update_configs(ctx).await
+    }
+}
+
+struct MeshEvent {
+    peer: PeerId,
+    event: SubEvent<SharedConfig>,
+}
+
+#[async_trait]
+impl OnEvent<MeshEvent> for ConfigLoader {
+    async fn handle(&mut self, msg: MeshEvent, ctx: &mut Context<Self>) -> Result<()> {
+        let config = match msg.event {
+            SubEvent::State(state) => state.borrow().config.clone(),
+            SubEvent::Event(SharedConfigEvent::Set { config }) => config,
+            SubEvent::Lost => {
+                // Values of a gone peer are stale, the layer is filled again when it's back
+                log::warn!("The mesh config layer of {} is lost", msg.peer);
+                Table::new()
+            }
+        };
+        if let Some(layer) = self.mesh.iter_mut().find(|layer| layer.peer == msg.peer) {
+            let mut config = Value::Table(config);
+            strip_local_keys(&mut config);
+            // Redacted secrets of the peer must not override anything
+            strip_redacted(&mut config);
+            layer.config = config;
+            self.schedule_update(Vec::new(), ctx)?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of the validation of a config segment by a consumer.
+pub struct SegmentStatus {
+    pub segment: Vec<String>,
+    pub error: Option<SegmentError>,
+}
+
+#[async_trait]
+impl OnEvent<SegmentStatus> for ConfigLoader {
+    async fn handle(&mut self, msg: SegmentStatus, _ctx: &mut Context<Self>) -> Result<()> {
+        let source = msg.segment.join(".");
+        let mut diagnostics = Vec::new();
+        if let Some(error) = msg.error {
+            let mut key = msg.segment;
+            key.extend(error.key);
+            diagnostics.push(self.locate(&key, error.message));
+        }
+        self.set_problems(source, diagnostics);
+        Ok(())
     }
 }
 
@@ -285,13 +1094,64 @@ impl ManageSubscription<ConfigUpdates> for ConfigLoader {
     }
 }
 
-pub struct StoreTemplate(pub Value);
+#[async_trait]
+impl OnEvent<Act<Configuration>> for ConfigLoader {
+    async fn handle(&mut self, msg: Act<Configuration>, _ctx: &mut Context<Self>) -> Result<()> {
+        let origin = msg.origin.as_deref();
+        match msg.action {
+            ConfigurationAction::Set { layer, key, value } => {
+                let path = key_path(&key);
+                let mut op = Operation::start(&format!("Writing {path} to {layer}"));
+                match self.write_value(origin, &layer, &key, &value).await {
+                    Ok(()) => {
+                        op.end(&format!("Complete writing {path} to {layer}"));
+                    }
+                    Err(err) => {
+                        op.failure(&format!("Can't write {path} to {layer}: {err}"));
+                        op.end(&format!("Config layer is not changed: {layer}"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Secret fields of segments provided by consumers of the config.
+pub struct ConfigSecrets(pub SegmentSecrets);
+
+#[async_trait]
+impl OnEvent<ConfigSecrets> for ConfigLoader {
+    async fn handle(&mut self, msg: ConfigSecrets, _ctx: &mut Context<Self>) -> Result<()> {
+        let mut secrets = self.stack.secrets.clone();
+        secrets.extend(msg.0);
+        if self.secrets != secrets {
+            self.secrets = secrets;
+            self.publish_config();
+            self.publish_shared();
+        }
+        Ok(())
+    }
+}
+
+/// The merged template of all consumers with documentation of keys.
+/// It's written only if the stack has the path for the template.
+pub struct StoreTemplate {
+    pub template: Value,
+    pub docs: Vec<KeyDoc>,
+}
 
 #[async_trait]
 impl OnEvent<StoreTemplate> for ConfigLoader {
     async fn handle(&mut self, msg: StoreTemplate, _ctx: &mut Context<Self>) -> Result<()> {
-        let content = toml::to_string_pretty(&msg.0)?;
-        fs::write(TEMPLATE_NAME, content).await?;
+        let Some(path) = &self.stack.template else {
+            return Ok(());
+        };
+        let content = document_template(&msg.template, &msg.docs, SCHEMA_NAME)?;
+        fs::write(path, content).await?;
+        let schema = json_schema(&msg.template, &msg.docs);
+        let content = serde_json::to_string_pretty(&schema)?;
+        fs::write(path.with_file_name(SCHEMA_NAME), content).await?;
         Ok(())
     }
 }
@@ -302,6 +1162,101 @@ pub fn wrap_level(title: &str, value: Value) -> Value {
     Value::Table(wrapper)
 }
 
+/// Converts a variable like `NINE__PARTICLE__OPENAI__CONFIG__API_KEY`
+/// into a config like `particle.openai.config.api_key`.
+/// Values are kept as strings and parsed by types of fields when segments
+/// are deserialized with `from_config`.
+pub fn env_value(name: &str, raw: &str) -> Option<Value> {
+    let path = name.strip_prefix(ENV_PREFIX)?;
+    let mut value = Value::String(raw.into());
+    for key in path.rsplit(ENV_SEPARATOR) {
+        if key.is_empty() {
+            return None;
+        }
+        value = wrap_level(&key.to_lowercase(), value);
+    }
+    Some(value)
+}
+
+/// Sets the value of the key in the TOML document.
+/// Comments and formatting of other entries are kept.
+pub fn set_value(content: &str, key: &[String], value: &Value) -> Result<String> {
+    let (last, parents) = key
+        .split_last()
+        .ok_or_else(|| anyhow!("The key is empty"))?;
+    let mut document: DocumentMut = content.parse()?;
+    let mut item = document.as_item_mut();
+    for part in parents {
+        let table = item
+            .as_table_like_mut()
+            .ok_or_else(|| anyhow!("The value of {part} is not a table"))?;
+        let mut implicit = toml_edit::Table::new();
+        implicit.set_implicit(true);
+        item = table
+            .entry(part)
+            .or_insert(toml_edit::Item::Table(implicit));
+    }
+    let table = item
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("The parent of {last} is not a table"))?;
+    let value: toml_edit::Value = value.to_string().parse()?;
+    match table.get_mut(last) {
+        Some(toml_edit::Item::Value(current)) => {
+            // Keep comments around the value
+            let decor = current.decor().clone();
+            *current = value;
+            *current.decor_mut() = decor;
+        }
+        _ => {
+            table.insert(last, toml_edit::value(value));
+        }
+    }
+    Ok(document.to_string())
+}
+
+/// Replaces values of secret keys with the placeholder.
+/// Tables are kept, since they are references to secrets.
+pub fn redact_keys(config: &mut Value, secrets: &[Vec<String>]) {
+    for key in secrets {
+        let mut item = Some(&mut *config);
+        for part in key {
+            item = item.and_then(|item| item.get_mut(part.as_str()));
+        }
+        if let Some(item) = item.filter(|item| !item.is_table()) {
+            *item = Value::String(REDACTED.into());
+        }
+    }
+}
+
+/// Removes keys of a mesh fragment that only local layers could set:
+/// fragments are not shared further and peers can't grant access to themselves.
+fn strip_local_keys(config: &mut Value) {
+    let Value::Table(table) = config else {
+        return;
+    };
+    table.remove(SHARE_KEY);
+    table.remove(INCLUDE_KEY);
+    table.remove(SHARE_PEERS_KEY);
+    table.remove(CONFIG_WRITERS_KEY);
+    // Profiles could set lists of peers as well
+    if let Some(Value::Table(profiles)) = table.get_mut(PROFILES_KEY) {
+        for profile in profiles.values_mut().filter_map(Value::as_table_mut) {
+            profile.remove(SHARE_PEERS_KEY);
+            profile.remove(CONFIG_WRITERS_KEY);
+        }
+    }
+}
+
+/// Removes values replaced with the placeholder by `redact_keys`.
+fn strip_redacted(config: &mut Value) {
+    if let Value::Table(table) = config {
+        table.retain(|_, item| item.as_str() != Some(REDACTED));
+        for item in table.values_mut() {
+            strip_redacted(item);
+        }
+    }
+}
+
 pub fn table() -> Value {
     Value::Table(Table::new())
 }
@@ -327,3 +1282,316 @@ pub fn merge_configs(base: &mut Value, overlay: &Value) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{channel, Receiver};
+    use std::time::Instant;
+
+    #[test]
+    fn test_env_value() {
+        let value = env_value("NINE__PARTICLE__OPENAI__CONFIG__API_KEY", "sk-key").unwrap();
+        let key = &value["particle"]["openai"]["config"]["api_key"];
+        assert_eq!(key.as_str(), Some("sk-key"));
+
+        let value = env_value("NINE__PARTICLE__ANTHROPIC__CONFIG__MAX_TOKENS", "512").unwrap();
+        let tokens = &value["particle"]["anthropic"]["config"]["max_tokens"];
+        assert_eq!(tokens.as_str(), Some("512"));
+
+        assert!(env_value("HOME", "/root").is_none());
+        assert!(env_value("NINE__PARTICLE____KEY", "value").is_none());
+    }
+
+    fn layer(path: &Path) -> ConfigLayer {
+        ConfigLayer {
+            path: Arc::new(path.to_path_buf()),
+            config: table(),
+            content: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parent_dir() {
+        assert_eq!(parent_dir(Path::new("nine.toml")), PathBuf::from("."));
+        assert_eq!(
+            parent_dir(Path::new("conf.d/a.toml")),
+            PathBuf::from("conf.d")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_atomic_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nine.toml");
+        std::fs::write(&path, "value = 1\n").unwrap();
+        let mut layer = layer(&path);
+        layer.read_config().await.unwrap();
+        assert_eq!(layer.config["value"].as_integer(), Some(1));
+
+        // Editors write a temporary file and rename it over the layer
+        let temp = dir.path().join(".nine.toml.swp");
+        std::fs::write(&temp, "value = 2\n").unwrap();
+        std::fs::rename(&temp, &path).unwrap();
+        let layers = [layer.path.clone()];
+        let affected = affected_layers(&layers, dir.path(), &[temp, path]);
+        assert_eq!(affected, layers);
+        layer.read_config().await.unwrap();
+        assert_eq!(layer.config["value"].as_integer(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_removed_layer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nine.toml");
+        std::fs::write(&path, "value = 1\n").unwrap();
+        let mut layer = layer(&path);
+        layer.read_config().await.unwrap();
+
+        // A removed layer is empty
+        std::fs::remove_file(&path).unwrap();
+        let layers = [layer.path.clone()];
+        let other = dir.path().join("other.toml");
+        assert!(affected_layers(&layers, dir.path(), &[other]).is_empty());
+        let affected = affected_layers(&layers, dir.path(), &[path.clone()]);
+        assert_eq!(affected, layers);
+        layer.read_config().await.unwrap();
+        assert_eq!(layer.config, table());
+
+        // And it's read again when created back
+        std::fs::write(&path, "value = 3\n").unwrap();
+        layer.read_config().await.unwrap();
+        assert_eq!(layer.config["value"].as_integer(), Some(3));
+    }
+
+    /// Waits for an event of the watcher that touches the layer
+    /// and skips the rest of events of the same change.
+    fn wait_layer_event(events: &Receiver<WatchResult>, dir: &Path, layers: &[Arc<PathBuf>]) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let event = events.recv_timeout(timeout).unwrap().unwrap();
+            let access = matches!(event.kind, EventKind::Access(_));
+            if !access && !affected_layers(layers, dir, &event.paths).is_empty() {
+                break;
+            }
+        }
+        while events.recv_timeout(Duration::from_millis(100)).is_ok() {}
+    }
+
+    #[tokio::test]
+    async fn test_watcher() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nine.toml");
+        std::fs::write(&path, "value = 1\n").unwrap();
+        let mut layer = layer(&path);
+        layer.read_config().await.unwrap();
+        let layers = [layer.path.clone()];
+        let (tx, events) = channel();
+        let _watcher = watch_dir(dir.path(), tx).unwrap();
+
+        // The layer is replaced by renaming a temporary file over it
+        let temp = dir.path().join(".nine.toml.swp");
+        std::fs::write(&temp, "value = 2\n").unwrap();
+        std::fs::rename(&temp, &path).unwrap();
+        wait_layer_event(&events, dir.path(), &layers);
+        layer.read_config().await.unwrap();
+        assert_eq!(layer.config["value"].as_integer(), Some(2));
+
+        // The watch of the directory survives removing the layer
+        std::fs::remove_file(&path).unwrap();
+        wait_layer_event(&events, dir.path(), &layers);
+        layer.read_config().await.unwrap();
+        assert_eq!(layer.config, table());
+
+        std::fs::write(&path, "value = 3\n").unwrap();
+        wait_layer_event(&events, dir.path(), &layers);
+        layer.read_config().await.unwrap();
+        assert_eq!(layer.config["value"].as_integer(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.toml");
+        std::fs::write(&base, "[a]\nb = 1\nc = 2\n").unwrap();
+        let local = dir.path().join("local.json");
+        std::fs::write(&local, r#"{ "a": { "b": 10 } }"#).unwrap();
+        let missing = dir.path().join("missing.toml");
+        let stack = ConfigStack::new().file(&base).file(&local).file(&missing);
+
+        let loader = ConfigLoader::load(stack).await;
+        let config = loader.merged_config();
+        assert_eq!(config["a"]["b"].as_integer(), Some(10));
+        assert_eq!(config["a"]["c"].as_integer(), Some(2));
+        assert_eq!(loader.origins()["a.b"], local.display().to_string());
+        assert_eq!(loader.origins()["a.c"], base.display().to_string());
+        assert!(loader.problems().is_empty());
+        // Tools don't create missing layers
+        assert!(!missing.exists());
+    }
+
+    #[tokio::test]
+    async fn test_load_profile() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.toml");
+        std::fs::write(&base, "[a]\nb = 1\nc = 2\n[profile.dev.a]\nb = 10\n").unwrap();
+        let stack = ConfigStack::new().file(&base).profile("dev");
+
+        let loader = ConfigLoader::load(stack).await;
+        let config = loader.merged_config();
+        assert_eq!(loader.profile(), Some("dev"));
+        assert_eq!(config["a"]["b"].as_integer(), Some(10));
+        assert!(config.get(PROFILES_KEY).is_none());
+        assert!(loader.origins().contains_key("a.b"));
+        assert!(!loader
+            .origins()
+            .keys()
+            .any(|key| key.starts_with("profile.")));
+
+        // Diagnostics point to the value of the profile
+        let key: Vec<String> = ["a", "b"].map(String::from).into();
+        let diagnostic = loader.locate(&key, "Invalid".into());
+        assert_eq!(diagnostic.line, Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_config_writers() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("base.toml");
+        let writer = PeerId::random();
+        std::fs::write(&base, format!("config_writers = [\"{writer}\"]\n")).unwrap();
+        let loader = ConfigLoader::load(ConfigStack::new().file(&base)).await;
+        assert!(loader.merged_config().get(CONFIG_WRITERS_KEY).is_none());
+
+        let layer = base.display().to_string();
+        let key: Vec<String> = ["a", "b"].map(String::from).into();
+        let stranger = PeerId::random().to_string();
+        let result = loader
+            .write_value(Some(&stranger), &layer, &key, &Value::Integer(1))
+            .await;
+        assert!(result.is_err());
+        let content = std::fs::read_to_string(&base).unwrap();
+        assert!(!content.contains("[a]"));
+
+        let writer = writer.to_string();
+        for origin in [Some(writer.as_str()), None] {
+            loader
+                .write_value(origin, &layer, &key, &Value::Integer(2))
+                .await
+                .unwrap();
+        }
+        let content = std::fs::read_to_string(&base).unwrap();
+        assert!(content.contains("b = 2"));
+    }
+
+    #[test]
+    fn test_layer_formats() {
+        let path = Path::new("conf.d/10-models.yaml");
+        assert_eq!(LayerFormat::of(path), LayerFormat::Yaml);
+        assert_eq!(LayerFormat::of(Path::new("nine")), LayerFormat::Toml);
+
+        let json = r#"{ "particle": { "anthropic": { "config": { "max_tokens": 512 } } } }"#;
+        let config = LayerFormat::Json.parse(json).unwrap();
+        let tokens = &config["particle"]["anthropic"]["config"]["max_tokens"];
+        assert_eq!(tokens.as_integer(), Some(512));
+
+        let yaml = "particle:\n  anthropic:\n    config:\n      model: claude-3-haiku\n";
+        let config = LayerFormat::Yaml.parse(yaml).unwrap();
+        let model = &config["particle"]["anthropic"]["config"]["model"];
+        assert_eq!(model.as_str(), Some("claude-3-haiku"));
+
+        let diagnostic = LayerFormat::Json
+            .parse("{\n  \"particle\": ,\n}")
+            .unwrap_err();
+        assert!(diagnostic.message.starts_with("Invalid JSON"));
+        assert_eq!(diagnostic.line, Some(2));
+
+        let diagnostic = LayerFormat::Yaml.parse("- one\n- two\n").unwrap_err();
+        assert!(diagnostic.message.contains("table"));
+    }
+
+    #[test]
+    fn test_track_origins() {
+        let mesh: Value = toml::from_str("[a]\nb = 1\nc = 2\nd = 3\n").unwrap();
+        let file: Value = toml::from_str("[a]\nb = 10\n[a.d]\ne = 4\n").unwrap();
+        let mut merged = table();
+        let mut origins = BTreeMap::new();
+        for (source, values) in [("mesh", &mesh), ("file", &file)] {
+            merge_configs(&mut merged, values);
+            track_origins(&mut origins, values, source, &mut Vec::new());
+        }
+        let origins: BTreeMap<_, _> = origins
+            .into_iter()
+            .filter(|(key, _)| lookup(&merged, key).map_or(false, |v| !v.is_table()))
+            .map(|(key, source)| (key_path(&key), source))
+            .collect();
+        assert_eq!(origins["a.b"], "file");
+        assert_eq!(origins["a.c"], "mesh");
+        assert_eq!(origins["a.d.e"], "file");
+        // The value was replaced by a table
+        assert!(!origins.contains_key("a.d"));
+    }
+
+    #[test]
+    fn test_shared_secrets() {
+        let mut config: Value =
+            toml::from_str("[particle.openai.config]\nmodel = \"gpt-4o\"\napi_key = \"sk-key\"\n")
+                .unwrap();
+        let secrets = vec![["particle", "openai", "config", "api_key"]
+            .map(String::from)
+            .to_vec()];
+        redact_keys(&mut config, &secrets);
+        let segment = &config["particle"]["openai"]["config"];
+        assert_eq!(segment["api_key"].as_str(), Some(REDACTED));
+
+        // Receivers don't take placeholders as values
+        strip_redacted(&mut config);
+        let segment = &config["particle"]["openai"]["config"];
+        assert!(segment.get("api_key").is_none());
+        assert_eq!(segment["model"].as_str(), Some("gpt-4o"));
+    }
+
+    #[tokio::test]
+    async fn test_withhold_unknown_segments() {
+        let api_key = ["particle", "openai", "config", "api_key"].map(String::from);
+        let stack = ConfigStack::new().secrets([("openai".to_string(), vec![api_key.to_vec()])]);
+        let loader = ConfigLoader::load(stack).await;
+        let config: Value = toml::from_str(
+            r#"
+            [particle.openai.config]
+            api_key = "sk-key"
+            [particle.anthropic.config]
+            api_key = "sk-ant-key"
+            "#,
+        )
+        .unwrap();
+        let config = loader.redacted(config);
+        let segment = &config["particle"]["openai"]["config"];
+        assert_eq!(segment["api_key"].as_str(), Some(REDACTED));
+        // Secret fields of the segment are unknown until its consumer is started
+        assert!(config["particle"].get("anthropic").is_none());
+    }
+
+    #[test]
+    fn test_set_value() {
+        let content = "# Models\n[particle.anthropic.config]\nmodel = \"claude\" # current\n";
+        let key: Vec<String> = ["particle", "anthropic", "config", "model"]
+            .map(String::from)
+            .into();
+        let value = Value::String("claude-3-7-sonnet".into());
+        let updated = set_value(content, &key, &value).unwrap();
+        assert_eq!(
+            updated,
+            "# Models\n[particle.anthropic.config]\nmodel = \"claude-3-7-sonnet\" # current\n"
+        );
+
+        let key: Vec<String> = ["particle", "openai", "config", "model"]
+            .map(String::from)
+            .into();
+        let updated = set_value(&updated, &key, &value).unwrap();
+        let config: Value = toml::from_str(&updated).unwrap();
+        let model = &config["particle"]["openai"]["config"]["model"];
+        assert_eq!(model.as_str(), Some("claude-3-7-sonnet"));
+    }
+}
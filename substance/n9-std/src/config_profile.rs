@@ -0,0 +1,74 @@
+use crate::config_loader::merge_configs;
+use toml::Value;
+
+/// The table of profiles, e.g. `[profile.dev]`.
+pub const PROFILES_KEY: &str = "profile";
+pub const ACTIVE_PROFILE_KEY: &str = "active_profile";
+
+/// The merged config with the overlay of the active profile.
+pub struct MergedConfig {
+    pub value: Value,
+    /// The profile that was overlaid
+    pub profile: Option<String>,
+}
+
+impl MergedConfig {
+    /// Overlays `[profile.<name>]` of the active profile on top of the base config.
+    /// The profile is chosen by the stack or by the `active_profile` key,
+    /// that could be set with the `NINE__ACTIVE_PROFILE` variable as well.
+    pub fn with_profile(mut value: Value, forced: Option<&str>) -> Self {
+        let mut profile = None;
+        if let Value::Table(table) = &mut value {
+            let profiles = table.remove(PROFILES_KEY);
+            let active = table.remove(ACTIVE_PROFILE_KEY);
+            let name = forced
+                .map(String::from)
+                .or_else(|| active.as_ref().and_then(Value::as_str).map(String::from));
+            if let Some(name) = name {
+                match profiles.as_ref().and_then(|profiles| profiles.get(&name)) {
+                    Some(overlay) => {
+                        merge_configs(&mut value, overlay);
+                        profile = Some(name);
+                    }
+                    None => {
+                        log::warn!("The config profile is not defined: {name}");
+                    }
+                }
+            }
+        }
+        Self { value, profile }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_overlay() {
+        let value: Value = toml::from_str(
+            r#"
+            active_profile = "dev"
+
+            [particle.anthropic.config]
+            model = "claude-3-opus"
+            max_tokens = 1024
+
+            [profile.dev.particle.anthropic.config]
+            model = "claude-3-haiku"
+            "#,
+        )
+        .unwrap();
+        let config = MergedConfig::with_profile(value.clone(), None);
+        let segment = &config.value["particle"]["anthropic"]["config"];
+        assert_eq!(segment["model"].as_str(), Some("claude-3-haiku"));
+        assert_eq!(segment["max_tokens"].as_integer(), Some(1024));
+        assert!(config.value.get("profile").is_none());
+
+        // The stack overrides the active profile
+        let config = MergedConfig::with_profile(value, Some("prod"));
+        let segment = &config.value["particle"]["anthropic"]["config"];
+        assert_eq!(segment["model"].as_str(), Some("claude-3-opus"));
+        assert_eq!(config.profile, None);
+    }
+}
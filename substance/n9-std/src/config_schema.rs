@@ -0,0 +1,216 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value as JsonValue};
+use toml::Value;
+use toml_edit::{DocumentMut, Item};
+
+/// Documentation of a field of a config segment.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldDoc {
+    /// A dotted path to the field relative to the segment
+    pub key: &'static str,
+    pub description: &'static str,
+    /// Allowed values, any value is allowed if it's empty
+    pub values: &'static [&'static str],
+}
+
+impl FieldDoc {
+    pub const fn new(key: &'static str, description: &'static str) -> Self {
+        Self {
+            key,
+            description,
+            values: &[],
+        }
+    }
+
+    pub const fn values(mut self, values: &'static [&'static str]) -> Self {
+        self.values = values;
+        self
+    }
+}
+
+/// Documentation of a key of the merged config.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyDoc {
+    pub key: Vec<String>,
+    pub description: String,
+    pub values: Vec<String>,
+    /// The value of the template, if it's not a secret
+    pub default: Option<Value>,
+}
+
+impl KeyDoc {
+    /// Lines of the comment placed before the key.
+    fn comment(&self) -> String {
+        let mut comment = String::new();
+        for line in self.description.lines() {
+            comment.push_str(&format!("# {line}\n"));
+        }
+        if !self.values.is_empty() {
+            comment.push_str(&format!("# Allowed values: {}\n", self.values.join(", ")));
+        }
+        comment
+    }
+}
+
+/// Renders the template with comments of documented keys.
+/// The schema directive lets editors validate and complete the config.
+pub fn document_template(template: &Value, docs: &[KeyDoc], schema: &str) -> Result<String> {
+    let content = toml::to_string_pretty(template)?;
+    let mut document: DocumentMut = content.parse()?;
+    for doc in docs {
+        let Some((last, parents)) = doc.key.split_last() else {
+            continue;
+        };
+        let mut item = Some(document.as_item_mut());
+        for part in parents {
+            item = item.and_then(|item| item.get_mut(part.as_str()));
+        }
+        let Some(table) = item.and_then(Item::as_table_like_mut) else {
+            continue;
+        };
+        let comment = doc.comment();
+        match table.get_mut(last) {
+            Some(Item::Table(table)) => {
+                table.decor_mut().set_prefix(format!("\n{comment}"));
+                continue;
+            }
+            Some(Item::ArrayOfTables(array)) => {
+                if let Some(table) = array.get_mut(0) {
+                    table.decor_mut().set_prefix(format!("\n{comment}"));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        if let Some(mut key) = table.key_mut(last) {
+            key.leaf_decor_mut().set_prefix(comment);
+        }
+    }
+    Ok(format!("#:schema ./{schema}\n\n{document}"))
+}
+
+/// Generates a JSON schema of the merged config using the template
+/// for the structure and types of values.
+pub fn json_schema(template: &Value, docs: &[KeyDoc]) -> JsonValue {
+    let mut schema = value_schema(template, &mut Vec::new(), docs);
+    if let JsonValue::Object(object) = &mut schema {
+        object.insert(
+            "$schema".into(),
+            "http://json-schema.org/draft-07/schema#".into(),
+        );
+    }
+    schema
+}
+
+fn value_schema(value: &Value, key: &mut Vec<String>, docs: &[KeyDoc]) -> JsonValue {
+    let mut schema = match value {
+        Value::Table(table) => {
+            let mut properties = Map::new();
+            for (name, item) in table {
+                key.push(name.clone());
+                properties.insert(name.clone(), value_schema(item, key, docs));
+                key.pop();
+            }
+            json!({ "type": "object", "properties": properties })
+        }
+        Value::Array(array) => {
+            let mut schema = json!({ "type": "array" });
+            if let Some(item) = array.first() {
+                schema["items"] = value_schema(item, key, docs);
+            }
+            schema
+        }
+        Value::String(_) | Value::Datetime(_) => json!({ "type": "string" }),
+        Value::Integer(_) => json!({ "type": "integer" }),
+        Value::Float(_) => json!({ "type": "number" }),
+        Value::Boolean(_) => json!({ "type": "boolean" }),
+    };
+    if let Some(doc) = docs.iter().find(|doc| &doc.key == key) {
+        schema["description"] = doc.description.clone().into();
+        if !doc.values.is_empty() {
+            schema["enum"] = doc.values.clone().into();
+        }
+        if let Some(default) = doc.default.as_ref() {
+            if let Ok(default) = serde_json::to_value(default) {
+                schema["default"] = default;
+            }
+        }
+    }
+    schema
+}
+
+/// Describes the key using the JSON schema of the config.
+pub fn explain(schema: &JsonValue, key: &str) -> Option<String> {
+    let mut node = schema;
+    for part in key.split('.') {
+        node = node
+            .get("properties")
+            .and_then(|properties| properties.get(part))
+            .or_else(|| node.get("items")?.get("properties")?.get(part))?;
+    }
+    let mut lines = vec![key.to_string()];
+    if let Some(description) = node.get("description").and_then(JsonValue::as_str) {
+        lines.push(format!("  {description}"));
+    }
+    if let Some(kind) = node.get("type").and_then(JsonValue::as_str) {
+        lines.push(format!("  Type: {kind}"));
+    }
+    if let Some(values) = node.get("enum").and_then(JsonValue::as_array) {
+        let values: Vec<_> = values.iter().filter_map(JsonValue::as_str).collect();
+        lines.push(format!("  Allowed values: {}", values.join(", ")));
+    }
+    if let Some(default) = node.get("default") {
+        lines.push(format!("  Default: {default}"));
+    }
+    if let Some(properties) = node.get("properties").and_then(JsonValue::as_object) {
+        let keys: Vec<_> = properties.keys().map(String::as_str).collect();
+        lines.push(format!("  Keys: {}", keys.join(", ")));
+    }
+    Some(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn docs() -> Vec<KeyDoc> {
+        vec![KeyDoc {
+            key: ["particle", "anthropic", "config", "model"]
+                .map(String::from)
+                .into(),
+            description: "The model used for requests".into(),
+            values: vec!["claude-3-opus".into(), "claude-3-haiku".into()],
+            default: Some(Value::String("claude-3-opus".into())),
+        }]
+    }
+
+    fn template() -> Value {
+        toml::from_str(
+            "[particle.anthropic.config]\nmodel = \"claude-3-opus\"\nmax_tokens = 1024\n",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_document_template() {
+        let content = document_template(&template(), &docs(), "nine.schema.json").unwrap();
+        assert!(content.starts_with("#:schema ./nine.schema.json\n"));
+        assert!(content.contains(
+            "# The model used for requests\n# Allowed values: claude-3-opus, claude-3-haiku\nmodel = "
+        ));
+        let parsed: Value = toml::from_str(&content).unwrap();
+        assert_eq!(parsed, template());
+    }
+
+    #[test]
+    fn test_explain() {
+        let schema = json_schema(&template(), &docs());
+        let text = explain(&schema, "particle.anthropic.config.model").unwrap();
+        assert!(text.contains("The model used for requests"));
+        assert!(text.contains("Default: \"claude-3-opus\""));
+        let text = explain(&schema, "particle.anthropic.config.max_tokens").unwrap();
+        assert!(text.contains("Type: integer"));
+        assert!(explain(&schema, "particle.openai").is_none());
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use ui9_net::tracers::peer::PeerId;
+
+const CONFIG_NAME: &str = "nine.toml";
+const CONFIG_DIR_NAME: &str = "conf.d";
+
+/// A source of config layers.
+#[derive(Debug, Clone)]
+pub enum LayerSpec {
+    /// A single file. It's created if `create` is set and the file doesn't exist.
+    File { path: PathBuf, create: bool },
+    /// All `*.toml`, `*.json` and `*.yaml` files of a directory merged in lexical order.
+    Directory { path: PathBuf },
+    /// The config shared by a trusted peer. Mesh layers are merged before files.
+    Mesh { peer: PeerId },
+}
+
+/// Full paths to secret fields by namespaces of config segments.
+pub type SegmentSecrets = BTreeMap<String, Vec<Vec<String>>>;
+
+/// Config layers in the order of priority: later layers override earlier ones.
+#[derive(Debug, Clone)]
+pub struct ConfigStack {
+    pub layers: Vec<LayerSpec>,
+    /// The profile that overrides the `active_profile` key of the config
+    pub profile: Option<String>,
+    /// Secret fields of segments that are published even before their consumers start
+    pub secrets: SegmentSecrets,
+    /// The file for the template of consumers of the config, the schema is placed next to it
+    pub template: Option<PathBuf>,
+}
+
+impl Default for ConfigStack {
+    /// `~/.config/nine/nine.toml`, config files of `~/.config/nine/conf.d` and `./nine.toml`
+    fn default() -> Self {
+        let mut stack = Self::new();
+        if let Some(home) = dirs::home_dir() {
+            let config_dir = home.join(".config").join("nine");
+            stack = stack
+                .file(config_dir.join(CONFIG_NAME))
+                .directory(config_dir.join(CONFIG_DIR_NAME));
+        }
+        stack.file(CONFIG_NAME)
+    }
+}
+
+impl ConfigStack {
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            profile: None,
+            secrets: SegmentSecrets::new(),
+            template: None,
+        }
+    }
+
+    /// Adds a file layer that is created if it doesn't exist.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.layers.push(LayerSpec::File { path, create: true });
+        self
+    }
+
+    /// Adds a file layer that is never created automatically.
+    pub fn read_only(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.layers.push(LayerSpec::File {
+            path,
+            create: false,
+        });
+        self
+    }
+
+    pub fn directory(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.layers.push(LayerSpec::Directory { path });
+        self
+    }
+
+    /// Adds the config shared by the peer as the layer with the lowest priority.
+    pub fn mesh(mut self, peer: PeerId) -> Self {
+        self.layers.push(LayerSpec::Mesh { peer });
+        self
+    }
+
+    pub fn profile(mut self, name: impl Into<String>) -> Self {
+        self.profile = Some(name.into());
+        self
+    }
+
+    /// Writes the template of particles that use the config when they are started.
+    pub fn template(mut self, path: impl Into<PathBuf>) -> Self {
+        self.template = Some(path.into());
+        self
+    }
+
+    /// Declares secret fields of segments, e.g. of all particles that could be launched.
+    pub fn secrets(
+        mut self,
+        secrets: impl IntoIterator<Item = (String, Vec<Vec<String>>)>,
+    ) -> Self {
+        self.secrets.extend(secrets);
+        self
+    }
+}
+
+/// Command line arguments to choose config layers.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct ConfigArgs {
+    /// Config files merged in the provided order. Replace the default layers.
+    #[arg(long = "config", short = 'c', value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+    /// Directories with `*.toml`, `*.json` or `*.yaml` files merged before the config files.
+    #[arg(long = "config-dir", value_name = "DIR")]
+    pub dirs: Vec<PathBuf>,
+    /// Trusted peers whose shared configs are merged below local files.
+    #[arg(long = "config-peer", value_name = "PEER_ID")]
+    pub peers: Vec<PeerId>,
+    /// The config profile overlaid on top of the base config.
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+    /// Write the template of started particles and the schema next to it.
+    #[arg(long, value_name = "FILE")]
+    pub write_template: Option<PathBuf>,
+}
+
+impl ConfigArgs {
+    /// Fails if an explicitly provided file doesn't exist.
+    pub fn stack(&self) -> Result<ConfigStack> {
+        let mut stack = if self.files.is_empty() && self.dirs.is_empty() {
+            ConfigStack::default()
+        } else {
+            let mut stack = ConfigStack::new();
+            for dir in &self.dirs {
+                stack = stack.directory(dir);
+            }
+            for file in &self.files {
+                if !file.is_file() {
+                    return Err(anyhow!("The config file is not found: {}", file.display()));
+                }
+                stack = stack.read_only(file);
+            }
+            stack
+        };
+        for peer in &self.peers {
+            stack = stack.mesh(*peer);
+        }
+        stack.profile = self.profile.clone();
+        stack.template = self.write_template.clone();
+        Ok(stack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explicit_files_must_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nine.toml");
+        let args = ConfigArgs {
+            files: vec![path.clone()],
+            ..ConfigArgs::default()
+        };
+        assert!(args.stack().is_err());
+
+        std::fs::write(&path, "").unwrap();
+        let stack = args.stack().unwrap();
+        assert!(matches!(
+            stack.layers.as_slice(),
+            [LayerSpec::File { create: false, .. }]
+        ));
+    }
+}
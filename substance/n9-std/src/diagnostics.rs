@@ -0,0 +1,223 @@
+use derive_more::{Deref, DerefMut, From, Into};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use toml_edit::{ImDocument, Item};
+use ui9::names::Fqn;
+use ui9_dui::{Flow, Listener, Publisher, Subscriber, Tracer, Unified};
+
+/// A problem found in the configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl ToString) -> Self {
+        Self {
+            file: None,
+            line: None,
+            column: None,
+            key: None,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn in_file(mut self, file: impl ToString) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+
+    /// Sets the line and the column of the byte offset in the content.
+    pub fn at(mut self, content: &str, offset: usize) -> Self {
+        let before = &content[..offset.min(content.len())];
+        let line_start = before.rfind('\n').map(|pos| pos + 1).unwrap_or(0);
+        self.line = Some(before.matches('\n').count() + 1);
+        self.column = Some(before[line_start..].chars().count() + 1);
+        self
+    }
+
+    /// Sets the line and the column reported by a parser, both start from 1.
+    pub fn at_position(mut self, line: usize, column: usize) -> Self {
+        self.line = Some(line);
+        self.column = Some(column);
+        self
+    }
+
+    pub fn with_key(mut self, key: &[String]) -> Self {
+        self.key = Some(key_path(key));
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}")?;
+            if let Some(line) = self.line {
+                write!(f, ":{line}")?;
+            }
+            if let Some(column) = self.column {
+                write!(f, ":{column}")?;
+            }
+            write!(f, ": ")?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, "`{key}`: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Renders a key like `particle.scheduler.config.entries[0].prompt`
+pub fn key_path(key: &[String]) -> String {
+    let mut path = String::new();
+    for part in key {
+        if part.parse::<usize>().is_ok() {
+            path.push_str(&format!("[{part}]"));
+        } else {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(part);
+        }
+    }
+    path
+}
+
+/// Finds the deepest existing part of the key in the document.
+/// Returns the depth and the span of the found item.
+pub fn locate(content: &str, key: &[String]) -> Option<(usize, Range<usize>)> {
+    let document = ImDocument::parse(content).ok()?;
+    let mut item: &Item = document.as_item();
+    let mut found = None;
+    for (depth, part) in key.iter().enumerate() {
+        let next = match part.parse::<usize>() {
+            Ok(index) if !item.is_table_like() => item.get(index),
+            _ => item.get(part.as_str()),
+        };
+        match next {
+            Some(next) => {
+                if let Some(span) = next.span() {
+                    found = Some((depth + 1, span));
+                }
+                item = next;
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+/// The reason why a config segment can't be used.
+#[derive(Debug, Clone)]
+pub struct SegmentError {
+    /// The path to the invalid value relative to the segment
+    pub key: Vec<String>,
+    pub message: String,
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct ConfigDiagnosticsSub {
+    listener: Listener<ConfigDiagnostics>,
+}
+
+impl Subscriber for ConfigDiagnostics {
+    type Driver = ConfigDiagnosticsSub;
+}
+
+#[derive(Deref, DerefMut, From, Into)]
+pub struct ConfigDiagnosticsPub {
+    tracer: Tracer<ConfigDiagnostics>,
+}
+
+impl Publisher for ConfigDiagnostics {
+    type Driver = ConfigDiagnosticsPub;
+}
+
+impl ConfigDiagnosticsPub {
+    pub fn set(&mut self, source: String, diagnostics: Vec<Diagnostic>) {
+        let event = ConfigDiagnosticsEvent::Set {
+            source,
+            diagnostics,
+        };
+        self.tracer.event(event);
+    }
+
+    pub fn clear(&mut self, source: String) {
+        let event = ConfigDiagnosticsEvent::Clear { source };
+        self.tracer.event(event);
+    }
+}
+
+impl Unified for ConfigDiagnostics {
+    fn fqn() -> Fqn {
+        Fqn::root("@config-diagnostics")
+    }
+}
+
+/// Current problems of the configuration grouped by a layer or a segment.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ConfigDiagnostics {
+    pub sources: BTreeMap<String, Vec<Diagnostic>>,
+}
+
+impl Flow for ConfigDiagnostics {
+    type Event = ConfigDiagnosticsEvent;
+    type Action = ();
+
+    fn apply(&mut self, event: Self::Event) {
+        match event {
+            ConfigDiagnosticsEvent::Set {
+                source,
+                diagnostics,
+            } => {
+                self.sources.insert(source, diagnostics);
+            }
+            ConfigDiagnosticsEvent::Clear { source } => {
+                self.sources.remove(&source);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ConfigDiagnosticsEvent {
+    Set {
+        source: String,
+        diagnostics: Vec<Diagnostic>,
+    },
+    Clear {
+        source: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(path: &str) -> Vec<String> {
+        path.split('.').map(String::from).collect()
+    }
+
+    #[test]
+    fn test_locate() {
+        let content = "[particle.telegram.config]\napi_key = 1\n";
+        let (depth, span) = locate(content, &key("particle.telegram.config.api_key")).unwrap();
+        assert_eq!(depth, 4);
+        let diagnostic = Diagnostic::new("invalid type").at(content, span.start);
+        assert_eq!(diagnostic.line, Some(2));
+        assert_eq!(diagnostic.column, Some(11));
+    }
+
+    #[test]
+    fn test_key_path() {
+        let path = key_path(&key("particle.scheduler.config.entries.0.prompt"));
+        assert_eq!(path, "particle.scheduler.config.entries[0].prompt");
+    }
+}
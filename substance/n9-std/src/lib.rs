@@ -1 +1,7 @@
+pub mod config_coerce;
+pub mod config_flow;
 pub mod config_loader;
+pub mod config_profile;
+pub mod config_schema;
+pub mod config_stack;
+pub mod diagnostics;
@@ -0,0 +1,260 @@
+use crate::launcher::{self, LauncherConfig};
+use anyhow::{anyhow, Result};
+use clap::Subcommand;
+use n9_core::keeper::interaction::GetConfig;
+use n9_core::{Config, ConfigArgs, ConfigStack};
+use n9_std::config_coerce::from_config;
+use n9_std::config_loader::{merge_configs, redact_keys, table, ConfigLoader, SCHEMA_NAME};
+use n9_std::config_schema::{document_template, explain, json_schema};
+use n9_std::diagnostics::{key_path, Diagnostic};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use toml::Value;
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Write a commented template for the particles
+    Init {
+        /// Particles of the launcher, the default set is used if it's empty
+        #[arg(long, value_delimiter = ',')]
+        particles: Vec<String>,
+        #[arg(long, default_value = "nine.toml")]
+        output: PathBuf,
+        /// Overwrite the existing config
+        #[arg(long)]
+        force: bool,
+    },
+    /// Validate layers and segments of launched particles
+    Check {
+        #[command(flatten)]
+        config: ConfigArgs,
+    },
+    /// Print the effective config with the origin layer of each key
+    Show {
+        #[command(flatten)]
+        config: ConfigArgs,
+    },
+    /// Compare the effective configs of two profiles
+    Diff {
+        left: String,
+        right: String,
+        #[command(flatten)]
+        config: ConfigArgs,
+    },
+    /// Describe a key using the schema generated by an agent
+    Explain {
+        /// A dotted key, e.g. `particle.anthropic.config.model`
+        key: String,
+        #[arg(long, default_value = "nine.schema.json")]
+        schema: PathBuf,
+    },
+}
+
+pub async fn execute(command: ConfigCommands) -> Result<()> {
+    match command {
+        ConfigCommands::Init {
+            particles,
+            output,
+            force,
+        } => init(particles, output, force),
+        ConfigCommands::Check { config } => check(config).await,
+        ConfigCommands::Show { config } => show(config).await,
+        ConfigCommands::Diff {
+            left,
+            right,
+            config,
+        } => diff(left, right, config).await,
+        ConfigCommands::Explain { key, schema } => {
+            let content = fs::read_to_string(&schema)?;
+            let schema = serde_json::from_str(&content)?;
+            let text =
+                explain(&schema, &key).ok_or_else(|| anyhow!("The key {key} is not documented"))?;
+            println!("{text}");
+            Ok(())
+        }
+    }
+}
+
+/// Writes the template of the launcher and the particles with the schema next to it.
+fn init(particles: Vec<String>, output: PathBuf, force: bool) -> Result<()> {
+    if output.exists() && !force {
+        return Err(anyhow!(
+            "The config {} exists already, use --force to overwrite it",
+            output.display()
+        ));
+    }
+    let particles = if particles.is_empty() {
+        LauncherConfig::template().particles
+    } else {
+        particles
+    };
+    if let Some(name) = particles.iter().find(|name| !launcher::is_known(name)) {
+        return Err(anyhow!("Unknown particle: {name}"));
+    }
+
+    let mut launcher = GetConfig::new::<LauncherConfig>()?;
+    let config = LauncherConfig {
+        particles: particles.clone(),
+        ..LauncherConfig::template()
+    };
+    launcher.template = Value::try_from(config)?;
+    let mut segments = launcher::segments(&particles)?;
+    segments.insert(0, launcher);
+
+    let mut template = table();
+    let mut docs = Vec::new();
+    for segment in &segments {
+        merge_configs(&mut template, &segment.scoped_template());
+        docs.extend(segment.key_docs());
+    }
+    docs.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let content = document_template(&template, &docs, SCHEMA_NAME)?;
+    fs::write(&output, content)?;
+    let schema = json_schema(&template, &docs);
+    fs::write(
+        output.with_file_name(SCHEMA_NAME),
+        serde_json::to_string_pretty(&schema)?,
+    )?;
+    println!("Created {}", output.display());
+    Ok(())
+}
+
+/// Reports problems of layers and segments of particles listed in the launcher.
+async fn check(args: ConfigArgs) -> Result<()> {
+    let stack = args.stack()?;
+    let loader = ConfigLoader::load(stack.clone()).await;
+    let mut diagnostics: Vec<Diagnostic> = loader.problems().values().flatten().cloned().collect();
+
+    if let Some(name) = stack
+        .profile
+        .as_ref()
+        .filter(|_| loader.profile().is_none())
+    {
+        let message = format!("The config profile is not defined: {name}");
+        diagnostics.push(Diagnostic::new(message));
+    }
+
+    let config = loader.merged_config();
+    let launcher = GetConfig::new::<LauncherConfig>()?;
+    let mut value = launcher.segment(config);
+    let particles = match check_segment(&loader, &launcher, &mut value) {
+        Some(diagnostic) => {
+            diagnostics.push(diagnostic);
+            Vec::new()
+        }
+        None => from_config::<LauncherConfig>(value)?.particles,
+    };
+    for (index, name) in particles.iter().enumerate() {
+        if !launcher::is_known(name) {
+            let mut key = launcher.path();
+            key.extend(["particles".to_string(), index.to_string()]);
+            diagnostics.push(loader.locate(&key, format!("Unknown particle: {name}")));
+        }
+    }
+    for segment in launcher::segments(&particles)? {
+        let mut value = segment.segment(config);
+        diagnostics.extend(check_segment(&loader, &segment, &mut value));
+    }
+
+    if diagnostics.is_empty() {
+        println!("The config is valid: {}", loader.sources().join(", "));
+        Ok(())
+    } else {
+        for diagnostic in &diagnostics {
+            eprintln!("{diagnostic}");
+        }
+        Err(anyhow!("{} config problems found", diagnostics.len()))
+    }
+}
+
+fn check_segment(loader: &ConfigLoader, seg: &GetConfig, value: &mut Value) -> Option<Diagnostic> {
+    let error = seg.check(value).err()?;
+    let mut key = seg.path();
+    key.extend(error.key);
+    Some(loader.locate(&key, error.message))
+}
+
+/// Prints values of the effective config with sources they came from.
+async fn show(args: ConfigArgs) -> Result<()> {
+    let loader = ConfigLoader::load(args.stack()?).await;
+    for diagnostic in loader.problems().values().flatten() {
+        eprintln!("{diagnostic}");
+    }
+
+    let config = redacted(loader.merged_config().clone())?;
+    println!("# Layers: {}", loader.sources().join(", "));
+    if let Some(profile) = loader.profile() {
+        println!("# Profile: {profile}");
+    }
+    for (key, value) in leaves(&config) {
+        match loader.origins().get(&key) {
+            Some(origin) => println!("{key} = {value}  # {origin}"),
+            None => println!("{key} = {value}"),
+        }
+    }
+    Ok(())
+}
+
+/// Prints keys that differ between profiles.
+async fn diff(left: String, right: String, args: ConfigArgs) -> Result<()> {
+    let stack = args.stack()?;
+    let left_leaves = profile_leaves(&stack, &left).await?;
+    let right_leaves = profile_leaves(&stack, &right).await?;
+    let keys: BTreeSet<_> = left_leaves.keys().chain(right_leaves.keys()).collect();
+    let mut equal = true;
+    for key in keys {
+        let line = match (left_leaves.get(key), right_leaves.get(key)) {
+            (Some(left), Some(right)) if left == right => continue,
+            (Some(left), Some(right)) => format!("~ {key}: {left} -> {right}"),
+            (Some(left), None) => format!("- {key} = {left}"),
+            (None, Some(right)) => format!("+ {key} = {right}"),
+            (None, None) => continue,
+        };
+        println!("{line}");
+        equal = false;
+    }
+    if equal {
+        println!("The profiles {left} and {right} are equal");
+    }
+    Ok(())
+}
+
+async fn profile_leaves(stack: &ConfigStack, name: &str) -> Result<BTreeMap<String, Value>> {
+    let loader = ConfigLoader::load(stack.clone().profile(name)).await;
+    if loader.profile().is_none() {
+        return Err(anyhow!("The config profile is not defined: {name}"));
+    }
+    let config = redacted(loader.merged_config().clone())?;
+    Ok(leaves(&config).into_iter().collect())
+}
+
+/// Redacts secret fields of all known particles.
+fn redacted(mut config: Value) -> Result<Value> {
+    let keys: Vec<_> = launcher::secret_keys()?.into_values().flatten().collect();
+    redact_keys(&mut config, &keys);
+    Ok(config)
+}
+
+/// Values of the config with their keys. Arrays are values as a whole.
+fn leaves(config: &Value) -> Vec<(String, Value)> {
+    let mut leaves = Vec::new();
+    collect_leaves(config, &mut Vec::new(), &mut leaves);
+    leaves
+}
+
+fn collect_leaves(value: &Value, key: &mut Vec<String>, leaves: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Table(table) => {
+            for (name, item) in table {
+                key.push(name.clone());
+                collect_leaves(item, key, leaves);
+                key.pop();
+            }
+        }
+        _ => {
+            leaves.push((key_path(key), value.clone()));
+        }
+    }
+}
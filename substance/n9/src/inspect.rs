@@ -0,0 +1,115 @@
+use anyhow::{anyhow, Result};
+use clap::{Args as ClapArgs, Subcommand};
+use serde_json::json;
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+use ui9::names::Fqn;
+use ui9_dui::flow::Dynamic;
+use ui9_dui::tracers::tree::Tree;
+use ui9_dui::{Flow, Listener, SubEvent, Unified};
+use ui9_net::tracers::peer::{Peer, PeerId};
+use ui9_net::RemoteExt;
+
+#[derive(Subcommand, Debug)]
+pub enum InspectCommands {
+    /// List peers discovered in the mesh
+    Peers {
+        /// Seconds to discover peers before printing them
+        #[arg(long, default_value_t = 3)]
+        wait: u64,
+        #[command(flatten)]
+        opts: InspectOpts,
+    },
+    /// Print the tree of flows of the peer
+    Tree {
+        /// The peer to inspect, the local node is inspected if it's not set
+        #[arg(long)]
+        peer: Option<PeerId>,
+        #[command(flatten)]
+        opts: InspectOpts,
+    },
+    /// Print the state of any flow decoded without knowing its type
+    Flow {
+        /// The name of the flow from the tree, e.g. `@job`
+        fqn: Fqn,
+        /// The peer to inspect, the local node is inspected if it's not set
+        #[arg(long)]
+        peer: Option<PeerId>,
+        #[command(flatten)]
+        opts: InspectOpts,
+    },
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct InspectOpts {
+    /// Keep printing events of the flow after the state
+    #[arg(long)]
+    follow: bool,
+    /// Seconds to wait for the state of the flow
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+}
+
+/// Prints the state and events of flows as JSON lines.
+pub async fn execute(command: InspectCommands) -> Result<()> {
+    match command {
+        InspectCommands::Peers { wait, opts } => {
+            let listener = Listener::<Peer>::local(Peer::fqn());
+            // The state is kept actual by the listener while peers are discovered
+            let delay = Duration::from_secs(wait);
+            print_flow(listener, delay, opts).await
+        }
+        InspectCommands::Tree { peer, opts } => {
+            let listener = listener::<Tree>(peer, Tree::fqn());
+            print_flow(listener, Duration::ZERO, opts).await
+        }
+        InspectCommands::Flow { fqn, peer, opts } => {
+            let listener = listener::<Dynamic>(peer, fqn);
+            print_flow(listener, Duration::ZERO, opts).await
+        }
+    }
+}
+
+pub fn listener<F: Flow>(peer: Option<PeerId>, fqn: Fqn) -> Listener<F> {
+    match peer {
+        Some(peer) => Listener::remote(peer, fqn),
+        None => Listener::local(fqn),
+    }
+}
+
+async fn print_flow<F: Flow>(
+    mut listener: Listener<F>,
+    delay: Duration,
+    opts: InspectOpts,
+) -> Result<()> {
+    let mut rx = listener.receiver()?;
+    let limit = Duration::from_secs(opts.timeout);
+    loop {
+        match timeout(limit, rx.recv()).await {
+            Ok(Some(SubEvent::State(state))) => {
+                sleep(delay).await;
+                // Events of the delay are reflected by the state already
+                while rx.try_recv().is_ok() {}
+                println!("{}", json!({ "state": &*state.borrow() }));
+                break;
+            }
+            Ok(Some(SubEvent::Lost)) => {
+                return Err(anyhow!("The flow is lost"));
+            }
+            Ok(Some(SubEvent::Event(_))) => {}
+            Ok(None) => return Err(anyhow!("The flow is closed")),
+            Err(_) => return Err(anyhow!("The flow is not available")),
+        }
+    }
+    if opts.follow {
+        while let Some(event) = rx.recv().await {
+            let line = match event {
+                SubEvent::State(state) => json!({ "state": &*state.borrow() }),
+                SubEvent::Event(event) => json!({ "event": event }),
+                SubEvent::Lost => json!({ "lost": true }),
+            };
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
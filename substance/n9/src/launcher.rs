@@ -0,0 +1,242 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use crb::agent::{Agent, AgentSession, Context, DoAsync, Next};
+use crb::superagent::Entry;
+use n9_core::keeper::interaction::GetConfig;
+use n9_core::{
+    Config, ConfigSegmentUpdates, FieldDoc, Particle, RestartConfig, RestartPolicy, SegmentSecrets,
+    SubstanceLink, SubstanceLinks, UpdateConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+type AddParticle = fn(&SubstanceLink, Option<RestartPolicy>) -> Result<()>;
+
+macro_rules! particles {
+    ($($name:literal => $particle:ty,)*) => {
+        /// Particles that could be launched by names of their crates without the `n9-` prefix.
+        const PARTICLES: &[(&str, AddParticle)] =
+            &[$(($name, |s, policy| match policy {
+                Some(policy) => s.add_particle_with::<$particle>(policy),
+                None => s.add_particle::<$particle>(),
+            }),)*];
+
+        /// Names of launchable particles allowed by the schema of the config.
+        const PARTICLE_NAMES: &[&str] = &[$($name,)*];
+    };
+}
+
+particles! {
+    "app-stdio" => n9_app_stdio::StdioApp,
+    "app-tui" => n9_app_tui::TuiApp,
+    "chat-telegram" => n9_chat_telegram::TelegramParticle,
+    "control-chat" => n9_control_chat::ChatParticle,
+    "control-scheduler" => n9_control_scheduler::SchedulerParticle,
+    "exchange-dydx" => n9_exchange_dydx::DyDxParticle,
+    "model-anthropic" => n9_model_anthropic::AnthropicParticle,
+    "model-mesh" => n9_model_mesh::ModelMeshParticle,
+    "model-mesh-provider" => n9_model_mesh::ModelProviderParticle,
+    "model-ollama" => n9_model_ollama::OllamaParticle,
+    "model-openai" => n9_model_openai::OpenAIParticle,
+    "model-rig" => n9_model_rig::RigModelParticle,
+    "tool-substance" => n9_tool_substance::SubstanceToolParticle,
+    "tool-substance-provider" => n9_tool_substance::SubstanceProviderParticle,
+}
+
+/// Particles that work only with the mesh network.
+const MESH_PARTICLES: &[&str] = &[
+    "model-mesh",
+    "model-mesh-provider",
+    "tool-substance",
+    "tool-substance-provider",
+];
+
+/// Set if the substance runs without the mesh network.
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Particles that need the mesh network are not launched after that.
+pub fn go_offline() {
+    OFFLINE.store(true, Ordering::Relaxed);
+}
+
+type GetSegment = fn() -> Result<GetConfig>;
+
+/// Config segments of particles. Particles without configs are not listed.
+const SEGMENTS: &[(&str, GetSegment)] = &[
+    (
+        "chat-telegram",
+        GetConfig::new::<n9_chat_telegram::TelegramConfig>,
+    ),
+    (
+        "control-scheduler",
+        GetConfig::new::<n9_control_scheduler::SchedulerConfig>,
+    ),
+    (
+        "exchange-dydx",
+        GetConfig::new::<n9_exchange_dydx::DyDxConfig>,
+    ),
+    (
+        "model-anthropic",
+        GetConfig::new::<n9_model_anthropic::AnthropicConfig>,
+    ),
+    (
+        "model-mesh-provider",
+        GetConfig::new::<n9_model_mesh::SharingConfig>,
+    ),
+    (
+        "model-ollama",
+        GetConfig::new::<n9_model_ollama::OllamaConfig>,
+    ),
+    (
+        "model-openai",
+        GetConfig::new::<n9_model_openai::OpenAIConfig>,
+    ),
+    ("model-rig", GetConfig::new::<n9_model_rig::RigConfig>),
+    (
+        "tool-substance-provider",
+        GetConfig::new::<n9_tool_substance::DelegationConfig>,
+    ),
+];
+
+/// Checks the particle could be launched by the name.
+pub fn is_known(name: &str) -> bool {
+    PARTICLES.iter().any(|(particle, _)| *particle == name)
+}
+
+/// Config segments of the particles.
+/// Unknown particles and particles without configs are skipped.
+pub fn segments(particles: &[String]) -> Result<Vec<GetConfig>> {
+    SEGMENTS
+        .iter()
+        .filter(|(name, _)| particles.iter().any(|particle| particle == name))
+        .map(|(_, get_segment)| get_segment())
+        .collect()
+}
+
+/// Config segments of all known particles.
+pub fn all_segments() -> Result<Vec<GetConfig>> {
+    SEGMENTS
+        .iter()
+        .map(|(_, get_segment)| get_segment())
+        .collect()
+}
+
+/// Secret fields of all known particles by their namespaces.
+pub fn secret_keys() -> Result<SegmentSecrets> {
+    let keys = all_segments()?
+        .iter()
+        .map(|segment| (segment.namespace.clone(), segment.secret_keys()))
+        .collect();
+    Ok(keys)
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct LauncherConfig {
+    pub particles: Vec<String>,
+    /// Restart policies of particles by their names,
+    /// particles not listed here use their own policies.
+    #[serde(default)]
+    pub restart: BTreeMap<String, RestartConfig>,
+}
+
+impl Config for LauncherConfig {
+    const NAMESPACE: &str = "launcher";
+    const FIELDS: &[FieldDoc] = &[
+        FieldDoc::new(
+            "particles",
+            "Particles added to the substance. Removed particles are stopped after a restart.",
+        )
+        .values(PARTICLE_NAMES),
+        FieldDoc::new(
+            "restart",
+            "Restart policies of particles by names, e.g. { app-stdio = { policy = \"on_failure\" } }",
+        ),
+    ];
+
+    fn template() -> Self {
+        Self {
+            particles: vec![
+                "model-openai".into(),
+                "control-chat".into(),
+                "app-tui".into(),
+            ],
+            restart: BTreeMap::new(),
+        }
+    }
+}
+
+/// Adds particles listed in the config to the substance.
+pub struct LauncherParticle {
+    substance: SubstanceLinks,
+    config_updates: Option<Entry<ConfigSegmentUpdates>>,
+    launched: BTreeSet<String>,
+}
+
+impl Particle for LauncherParticle {
+    fn construct(substance: SubstanceLinks) -> Self {
+        Self {
+            substance,
+            config_updates: None,
+            launched: BTreeSet::new(),
+        }
+    }
+}
+
+impl Agent for LauncherParticle {
+    type Context = AgentSession<Self>;
+
+    fn begin(&mut self) -> Next<Self> {
+        Next::do_async(Initialize)
+    }
+}
+
+struct Initialize;
+
+#[async_trait]
+impl DoAsync<Initialize> for LauncherParticle {
+    async fn handle(&mut self, _: Initialize, ctx: &mut Context<Self>) -> Result<Next<Self>> {
+        let mut bond = self.substance.bond(&ctx);
+        let (config, entry) = bond.live_config_updates().await?;
+        self.config_updates = Some(entry);
+        self.update_config(config, ctx).await?;
+        Ok(Next::events())
+    }
+}
+
+#[async_trait]
+impl UpdateConfig<LauncherConfig> for LauncherParticle {
+    async fn update_config(
+        &mut self,
+        config: LauncherConfig,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        for name in &config.particles {
+            if self.launched.contains(name) {
+                continue;
+            }
+            if OFFLINE.load(Ordering::Relaxed) && MESH_PARTICLES.contains(&name.as_str()) {
+                log::error!(
+                    "The particle {name} needs the mesh network and is not launched offline"
+                );
+                continue;
+            }
+            match PARTICLES.iter().find(|(particle, _)| particle == name) {
+                Some((_, add_particle)) => {
+                    let policy = config.restart.get(name).cloned().map(RestartPolicy::from);
+                    add_particle(&self.substance.substance, policy)?;
+                    self.launched.insert(name.clone());
+                }
+                None => {
+                    log::error!("Unknown particle: {name}");
+                }
+            }
+        }
+        for name in &self.launched {
+            if !config.particles.contains(name) {
+                log::warn!("The particle {name} will be stopped after a restart");
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,15 +1,125 @@
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+mod config;
+mod inspect;
+mod launcher;
+mod session;
 
-fn main() -> Result<()> {
+use anyhow::{anyhow, Result};
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use config::ConfigCommands;
+use crb::agent::RunAgent;
+use inspect::InspectCommands;
+use launcher::LauncherParticle;
+use log::LevelFilter;
+use n9_app_tui::MakerTui;
+use n9_core::{ConfigArgs, ConfigStack, Substance};
+use session::{RecordArgs, ReplayArgs};
+use ui9_maker::App;
+use ui9_mesh::Mesh;
+
+#[tokio::main]
+async fn main() -> Result<()> {
     let args = Args::parse();
     match args.command {
         None => {}
-        Some(Commands::Maker) => {}
+        Some(Commands::Maker(args)) => {
+            maker(args).await?;
+            // Unblocking stdin
+            std::process::exit(0);
+        }
+        Some(Commands::Run(args)) => {
+            run(args).await?;
+            // Unblocking stdin
+            std::process::exit(0);
+        }
+        Some(Commands::Config { command }) => {
+            config::execute(command).await?;
+        }
+        Some(Commands::Inspect { command }) => {
+            Mesh::activate().await?;
+            let result = inspect::execute(command).await;
+            Mesh::deactivate().await?;
+            result?;
+        }
+        Some(Commands::Record(args)) => {
+            Mesh::activate().await?;
+            let result = session::record(args).await;
+            Mesh::deactivate().await?;
+            result?;
+        }
+        Some(Commands::Replay(args)) => {
+            env_logger::try_init()?;
+            Mesh::activate().await?;
+            let result = session::replay(args).await;
+            Mesh::deactivate().await?;
+            result?;
+        }
+    }
+    Ok(())
+}
+
+/// Starts a substance with particles listed in `particle.launcher.config`.
+async fn run(args: RunArgs) -> Result<()> {
+    let mut logger = env_logger::Builder::from_default_env();
+    if let Some(level) = args.log_level {
+        logger.filter_level(level);
+    }
+    logger.try_init()?;
+
+    // Secrets of particles are redacted before the particles are started
+    let stack = args.config.stack()?.secrets(launcher::secret_keys()?);
+    if args.offline {
+        if !args.config.peers.is_empty() {
+            return Err(anyhow!("Config peers are not available offline"));
+        }
+        launcher::go_offline();
+    } else {
+        Mesh::activate().await?;
+    }
+    let result = launch(stack).await;
+    if !args.offline {
+        Mesh::deactivate().await?;
     }
+    result
+}
+
+async fn launch(stack: ConfigStack) -> Result<()> {
+    let mut substance = Substance::arise_with(stack);
+    substance.add_particle::<LauncherParticle>()?;
+    substance.join().await?;
     Ok(())
 }
 
+/// Joins the mesh without a substance and shows the dashboard of peers.
+async fn maker(args: MakerArgs) -> Result<()> {
+    Mesh::activate().await?;
+    let (app, link) = App::new();
+    if args.gui {
+        env_logger::try_init()?;
+        let app = tokio::spawn(app);
+        open_gui(link)?;
+        app.await?;
+    } else {
+        // The terminal is occupied by the dashboard, so logs are not printed
+        let tui = RunAgent::new(MakerTui::new(link));
+        tokio::join!(app, tui);
+    }
+    Mesh::deactivate().await?;
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+fn open_gui(link: ui9_maker::AppLink) -> Result<()> {
+    // The window has to be driven by the main thread
+    tokio::task::block_in_place(|| ice9_maker_gui::AppGui::entrypoint(link));
+    Ok(())
+}
+
+#[cfg(not(feature = "gui"))]
+fn open_gui(link: ui9_maker::AppLink) -> Result<()> {
+    link.address.interrupt()?;
+    Err(anyhow!("n9 is built without the `gui` feature"))
+}
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -19,5 +129,41 @@ struct Args {
 
 #[derive(Subcommand, Debug)]
 enum Commands {
-    Maker,
+    /// Open the dashboard of the mesh network
+    Maker(MakerArgs),
+    /// Run an agent assembled from particles of the config
+    Run(RunArgs),
+    /// Create, check and inspect the configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Print flows of the local node or mesh peers as JSON lines
+    Inspect {
+        #[command(subcommand)]
+        command: InspectCommands,
+    },
+    /// Record the traffic of flows to a file
+    Record(RecordArgs),
+    /// Publish recorded flows as if they were live
+    Replay(ReplayArgs),
+}
+
+#[derive(ClapArgs, Debug)]
+struct MakerArgs {
+    /// Open a window instead of the terminal dashboard (requires the `gui` feature)
+    #[arg(long)]
+    gui: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct RunArgs {
+    #[command(flatten)]
+    config: ConfigArgs,
+    /// Overrides the level of `RUST_LOG`
+    #[arg(long, value_name = "LEVEL")]
+    log_level: Option<LevelFilter>,
+    /// Don't join the mesh network
+    #[arg(long)]
+    offline: bool,
 }
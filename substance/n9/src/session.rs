@@ -0,0 +1,109 @@
+use crate::inspect::listener;
+use anyhow::{anyhow, Result};
+use clap::Args as ClapArgs;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::time::{sleep, timeout};
+use ui9::names::Fqn;
+use ui9_dui::flow::Raw;
+use ui9_dui::session::{read_session, SessionPlayer, SessionWriter};
+use ui9_dui::tracers::tree::Tree;
+use ui9_dui::{Listener, SubEvent, Unified};
+use ui9_net::tracers::peer::PeerId;
+
+#[derive(ClapArgs, Debug)]
+pub struct RecordArgs {
+    /// Flows to record, e.g. `@job`
+    #[arg(required = true)]
+    fqns: Vec<Fqn>,
+    /// The peer to record, the local node is recorded if it's not set
+    #[arg(long)]
+    peer: Option<PeerId>,
+    #[arg(long, short, default_value = "session.jsonl")]
+    output: PathBuf,
+    /// Seconds to record, the recording lasts until Ctrl+C if it's not set
+    #[arg(long)]
+    duration: Option<u64>,
+    /// Seconds to wait for the tree of flows
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct ReplayArgs {
+    /// A session written by `n9 record`
+    input: PathBuf,
+    /// Multiplies the pace of recorded events
+    #[arg(long, default_value_t = 1.0)]
+    speed: f64,
+    /// Publishes flows under the prefix to avoid conflicts with local ones, e.g. `@peer`
+    #[arg(long)]
+    prefix: Option<Fqn>,
+    /// Exit when the session is over instead of keeping flows published
+    #[arg(long)]
+    exit: bool,
+}
+
+/// Writes the traffic of flows to the file.
+pub async fn record(args: RecordArgs) -> Result<()> {
+    // The class of a flow is taken from the tree of the node
+    let mut tree_listener = listener::<Tree>(args.peer, Tree::fqn());
+    let mut rx = tree_listener.receiver()?;
+    let limit = Duration::from_secs(args.timeout);
+    let tree = loop {
+        match timeout(limit, rx.recv()).await {
+            Ok(Some(SubEvent::State(state))) => break state.borrow().clone(),
+            Ok(Some(_)) => {}
+            Ok(None) | Err(_) => return Err(anyhow!("The tree of flows is not available")),
+        }
+    };
+    let mut flows = Vec::new();
+    for fqn in args.fqns {
+        let info = tree
+            .root
+            .find(&fqn)
+            .and_then(|level| level.tracer_info.clone())
+            .ok_or_else(|| anyhow!("The flow {fqn} is not found"))?;
+        let listener = listener::<Raw>(args.peer, fqn.clone());
+        flows.push((fqn, info, listener));
+    }
+
+    let file = File::create(&args.output)?;
+    let mut writer = SessionWriter::new(BufWriter::new(file));
+    let duration = async {
+        match args.duration {
+            Some(secs) => sleep(Duration::from_secs(secs)).await,
+            None => std::future::pending().await,
+        }
+    };
+    eprintln!(
+        "Recording to {}, press Ctrl+C to stop",
+        args.output.display()
+    );
+    tokio::select! {
+        result = writer.record(flows) => result?,
+        result = tokio::signal::ctrl_c() => result?,
+        _ = duration => {}
+    }
+    eprintln!("The session is saved to {}", args.output.display());
+    Ok(())
+}
+
+/// Publishes recorded flows, so they can be watched with `n9 maker`.
+pub async fn replay(args: ReplayArgs) -> Result<()> {
+    let file = File::open(&args.input)?;
+    let records = read_session(BufReader::new(file))?;
+    let mut player = SessionPlayer::new(args.prefix);
+    eprintln!("Replaying {} records", records.len());
+    tokio::select! {
+        result = player.play(records, args.speed) => result?,
+        result = tokio::signal::ctrl_c() => return Ok(result?),
+    }
+    if !args.exit {
+        eprintln!("The session is over, press Ctrl+C to exit");
+        tokio::signal::ctrl_c().await?;
+    }
+    Ok(())
+}
@@ -18,6 +18,11 @@ pub mod xml;
 #[cfg(feature = "xml")]
 pub use xml::Xml;
 
+#[cfg(feature = "yaml")]
+pub mod yaml;
+#[cfg(feature = "yaml")]
+pub use yaml::Yaml;
+
 use anyhow::Error;
 use serde::{de::DeserializeOwned, Serialize};
 
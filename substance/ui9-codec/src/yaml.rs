@@ -0,0 +1,41 @@
+use crate::{Codec, ProtocolCodec, ProtocolData};
+use anyhow::{Error, Result};
+use derive_more::{AsRef, Deref, DerefMut, From};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The text codec that uses YAML.
+#[derive(Debug)]
+pub struct YamlCodec;
+
+impl ProtocolCodec for YamlCodec {
+    fn decode<T: ProtocolData>(data: &[u8]) -> Result<T> {
+        Yaml::decode(data)
+    }
+
+    fn encode<T: ProtocolData>(value: &T) -> Result<Vec<u8>> {
+        Yaml::encode(value)
+    }
+}
+
+#[derive(Debug, From, Deref, DerefMut, AsRef)]
+pub struct Yaml<T>(pub T);
+
+impl<T> Codec for Yaml<T> {
+    type Target = T;
+
+    fn decode(data: &[u8]) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        serde_yaml::from_slice(data).map_err(Error::from)
+    }
+
+    fn encode(value: &T) -> Result<Vec<u8>>
+    where
+        T: Serialize,
+    {
+        serde_yaml::to_string(value)
+            .map(String::into_bytes)
+            .map_err(Error::from)
+    }
+}
@@ -0,0 +1,148 @@
+use super::Flow;
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Map, Number, Value};
+use std::fmt;
+
+/// A flow of any type decoded without knowing it.
+///
+/// Events are not applied, since the logic of the flow is unknown,
+/// so the state is the one received on subscribing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Dynamic {
+    pub value: Generic,
+}
+
+impl Flow for Dynamic {
+    type Event = Generic;
+    type Action = ();
+
+    fn apply(&mut self, _event: Self::Event) {}
+}
+
+/// Any self-describing value represented as JSON.
+/// Byte arrays become arrays of numbers and non-string keys are stringified.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Generic(pub Value);
+
+impl Serialize for Generic {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Generic {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(GenericVisitor)
+    }
+}
+
+struct GenericVisitor;
+
+impl<'de> Visitor<'de> for GenericVisitor {
+    type Value = Generic;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Generic, E> {
+        Ok(Generic(Value::Bool(v)))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Generic, E> {
+        Ok(Generic(Value::Number(v.into())))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Generic, E> {
+        Ok(Generic(Value::Number(v.into())))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Generic, E> {
+        Ok(Generic(
+            Number::from_f64(v).map_or(Value::Null, Value::Number),
+        ))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Generic, E> {
+        Ok(Generic(Value::String(v.into())))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Generic, E> {
+        Ok(Generic(Value::String(v)))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Generic, E> {
+        let bytes = v.iter().map(|byte| Value::Number((*byte).into())).collect();
+        Ok(Generic(Value::Array(bytes)))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Generic, E> {
+        Ok(Generic(Value::Null))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Generic, E> {
+        Ok(Generic(Value::Null))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Generic, D::Error> {
+        Generic::deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Generic, D::Error> {
+        Generic::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Generic, A::Error> {
+        let mut items = Vec::new();
+        while let Some(Generic(item)) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Generic(Value::Array(items)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Generic, A::Error> {
+        let mut object = Map::new();
+        while let Some((Generic(key), Generic(value))) = map.next_entry()? {
+            let key = match key {
+                Value::String(key) => key,
+                other => other.to_string(),
+            };
+            object.insert(key, value);
+        }
+        Ok(Generic(Value::Object(object)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracers::tree::{Tree, TreeEvent};
+    use crate::TracerInfo;
+    use ui9::names::Fqn;
+
+    #[test]
+    fn test_decode_unknown_flow() {
+        let mut tree = Tree::default();
+        let fqn = Fqn::from_iter(["agent", "chat"]);
+        let info = TracerInfo {
+            class: "Chat".into(),
+        };
+        tree.apply(TreeEvent::AddFlow {
+            fqn: fqn.clone(),
+            info,
+        });
+        let packed = tree.pack_state().unwrap();
+        let state = Dynamic::unpack_state(&packed).unwrap();
+        let class = &state.value.0["root"]["levels"]["agent"]["levels"]["chat"]["tracer_info"];
+        assert_eq!(class["class"], "Chat");
+
+        let packed = Tree::pack_event(&TreeEvent::DelFlow { fqn }).unwrap();
+        let event = Dynamic::unpack_event(&packed).unwrap();
+        assert_eq!(event.0["DelFlow"]["fqn"]["components"][1], "chat");
+    }
+}
@@ -1,9 +1,13 @@
+pub mod dynamic;
 pub mod encoding;
 pub mod flow;
 pub mod packed;
+pub mod raw;
 
+pub use dynamic::{Dynamic, Generic};
 pub use flow::Flow;
 pub use packed::{PackedAction, PackedEvent, PackedState};
+pub use raw::Raw;
 
 use ui9::names::Fqn;
 
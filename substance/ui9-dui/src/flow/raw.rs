@@ -0,0 +1,46 @@
+use super::packed::{PackedAction, PackedEvent, PackedState};
+use super::Flow;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// A flow of any type kept packed.
+///
+/// Data is passed as is, so the traffic of a flow can be recorded
+/// and published again without knowing its type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Raw {
+    pub state: PackedState,
+}
+
+impl Flow for Raw {
+    type Event = PackedEvent;
+    type Action = PackedAction;
+
+    fn apply(&mut self, _event: Self::Event) {}
+
+    fn pack_state(&self) -> Result<PackedState> {
+        Ok(self.state.clone())
+    }
+
+    fn unpack_state(data: &PackedState) -> Result<Self> {
+        Ok(Self {
+            state: data.clone(),
+        })
+    }
+
+    fn pack_event(delta: &Self::Event) -> Result<PackedEvent> {
+        Ok(delta.clone())
+    }
+
+    fn unpack_event(data: &PackedEvent) -> Result<Self::Event> {
+        Ok(data.clone())
+    }
+
+    fn pack_action(action: &Self::Action) -> Result<PackedAction> {
+        Ok(action.clone())
+    }
+
+    fn unpack_action(data: &PackedAction) -> Result<Self::Action> {
+        Ok(data.clone())
+    }
+}
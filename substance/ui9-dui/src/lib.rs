@@ -2,6 +2,7 @@ pub mod flow;
 pub mod hub;
 pub mod publisher;
 pub mod reporter;
+pub mod session;
 pub mod subscriber;
 pub mod tracers;
 
@@ -1,8 +1,10 @@
 mod recorder;
+mod replayer;
 mod server;
 mod tracer;
 
 pub use recorder::{EventFlow, Recorder, RecorderLink, UniRecorder};
+pub use replayer::{Replay, Replayer};
 pub use server::{HubServer, HubServerLink};
 pub use tracer::{Tracer, TracerInfo};
 
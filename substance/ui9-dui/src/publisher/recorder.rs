@@ -34,7 +34,19 @@ impl RecorderLink {
     }
 
     pub fn act(&mut self, action: PackedAction) -> Fetcher<()> {
-        let action = Action { action };
+        let action = Action {
+            action,
+            origin: None,
+        };
+        self.address.interact(action)
+    }
+
+    /// Sends the action received from the remote peer.
+    pub fn act_from(&mut self, action: PackedAction, origin: String) -> Fetcher<()> {
+        let action = Action {
+            action,
+            origin: Some(origin),
+        };
         self.address.interact(action)
     }
 }
@@ -92,7 +104,7 @@ impl<F: Flow> OnEvent<Update<F>> for Recorder<F> {
 }
 
 pub struct EventFlow {
-    recipient: Recipient<PackedEvent>,
+    pub(super) recipient: Recipient<PackedEvent>,
 }
 
 impl Subscription for EventFlow {
@@ -123,6 +135,7 @@ impl<F: Flow> ManageSubscription<EventFlow> for Recorder<F> {
 
 pub struct Action {
     action: PackedAction,
+    origin: Option<String>,
 }
 
 impl Request for Action {
@@ -133,7 +146,10 @@ impl Request for Action {
 impl<F: Flow> OnRequest<Action> for Recorder<F> {
     async fn on_request(&mut self, request: Action, _ctx: &mut Context<Self>) -> Result<()> {
         let action = F::unpack_action(&request.action)?;
-        let msg = Act { action };
+        let msg = Act {
+            action,
+            origin: request.origin,
+        };
         self.state.action_tx.send(msg)?;
         Ok(())
     }
@@ -0,0 +1,158 @@
+use super::recorder::{Action, EventFlow};
+use super::server::HubServer;
+use super::TracerInfo;
+use crate::flow::{PackedEvent, PackedState};
+use anyhow::Result;
+use async_trait::async_trait;
+use crb::agent::{Address, Agent, AgentSession, Context, OnEvent, StopAddress};
+use crb::core::Unique;
+use crb::send::Sender;
+use crb::superagent::{ManageSubscription, OnRequest};
+use std::collections::{HashSet, VecDeque};
+use ui9::names::Fqn;
+
+/// Events kept for late subscribers of a replayed flow.
+const MAX_HISTORY: usize = 10_000;
+
+/// Publishes packed data of a flow without knowing its type.
+///
+/// Events can't be applied to the packed state, so they are kept
+/// and sent to late subscribers right after the initial state.
+/// Only the last `MAX_HISTORY` events are kept, late subscribers
+/// of a long session get the initial state with recent events only.
+pub struct Replay {
+    replayer: StopAddress<Replayer>,
+}
+
+impl Replay {
+    pub fn new(fqn: Fqn, info: TracerInfo, state: PackedState) -> Self {
+        let replayer = HubServer::spawn_replayer(fqn, info, state);
+        Self { replayer }
+    }
+
+    pub fn event(&self, event: PackedEvent) {
+        self.replayer.event(event).ok();
+    }
+}
+
+pub struct Replayer {
+    state: PackedState,
+    history: History,
+    subscribers: HashSet<Unique<EventFlow>>,
+}
+
+impl Replayer {
+    pub fn new(state: PackedState) -> Self {
+        Self {
+            state,
+            history: History::new(MAX_HISTORY),
+            subscribers: HashSet::new(),
+        }
+    }
+}
+
+/// The bounded history of events, the oldest events are dropped first.
+struct History {
+    events: VecDeque<PackedEvent>,
+    limit: usize,
+    truncated: bool,
+}
+
+impl History {
+    fn new(limit: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            limit,
+            truncated: false,
+        }
+    }
+
+    fn push(&mut self, event: PackedEvent) {
+        if self.events.len() == self.limit {
+            self.events.pop_front();
+            if !self.truncated {
+                log::warn!(
+                    "The history of the replayed flow exceeds {} events",
+                    self.limit
+                );
+                self.truncated = true;
+            }
+        }
+        self.events.push_back(event);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &PackedEvent> {
+        self.events.iter()
+    }
+}
+
+impl Agent for Replayer {
+    type Context = AgentSession<Self>;
+}
+
+#[async_trait]
+impl OnEvent<PackedEvent> for Replayer {
+    async fn handle(&mut self, event: PackedEvent, _ctx: &mut Context<Self>) -> Result<()> {
+        for subscriber in &self.subscribers {
+            subscriber.recipient.send(event.clone()).ok();
+        }
+        self.history.push(event);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ManageSubscription<EventFlow> for Replayer {
+    async fn subscribe(
+        &mut self,
+        sub: Unique<EventFlow>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<PackedState> {
+        // Players handle queued events after the state is assigned
+        for event in self.history.iter() {
+            sub.recipient.send(event.clone()).ok();
+        }
+        self.subscribers.insert(sub);
+        Ok(self.state.clone())
+    }
+
+    async fn unsubscribe(
+        &mut self,
+        sub: Unique<EventFlow>,
+        _ctx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.subscribers.remove(&sub);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OnRequest<Action> for Replayer {
+    async fn on_request(&mut self, _request: Action, _ctx: &mut Context<Self>) -> Result<()> {
+        log::debug!("An action to the replayed flow is ignored");
+        Ok(())
+    }
+}
+
+impl super::UniRecorder for Address<Replayer> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_session_history() {
+        let mut history = History::new(MAX_HISTORY);
+        let total = MAX_HISTORY * 3 + 7;
+        for index in 0..total {
+            let event = PackedEvent((index as u32).to_be_bytes().to_vec());
+            history.push(event);
+        }
+        assert_eq!(history.iter().count(), MAX_HISTORY);
+        // Late subscribers get the most recent events in order
+        let first = history.iter().next().unwrap();
+        assert_eq!(first.0, ((total - MAX_HISTORY) as u32).to_be_bytes());
+        let last = history.iter().last().unwrap();
+        assert_eq!(last.0, ((total - 1) as u32).to_be_bytes());
+    }
+}
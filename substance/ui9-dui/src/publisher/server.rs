@@ -1,5 +1,5 @@
-use super::{Pub, Recorder, RecorderLink, RecorderState, TracerInfo};
-use crate::flow::Flow;
+use super::{Pub, Recorder, RecorderLink, RecorderState, Replayer, TracerInfo};
+use crate::flow::{Flow, PackedState};
 use crate::tracers::tree::Tree;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
@@ -52,6 +52,24 @@ impl HubServer {
         PUB_BRIDGE.send(delegate);
         address.to_stop_address()
     }
+
+    pub fn spawn_replayer(
+        fqn: Fqn,
+        tracer_info: TracerInfo,
+        state: PackedState,
+    ) -> StopAddress<Replayer> {
+        let replayer = Replayer::new(state);
+        let runtime = RunAgent::new(replayer);
+        let address = runtime.address();
+        let delegate = Delegate {
+            fqn,
+            tracer_info,
+            link: RecorderLink::new(runtime.address().clone()),
+            runtime: Box::new(runtime),
+        };
+        PUB_BRIDGE.send(delegate);
+        address.to_stop_address()
+    }
 }
 
 pub struct HubServer {
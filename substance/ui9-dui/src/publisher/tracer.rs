@@ -10,7 +10,7 @@ use crb::superagent::Drainer;
 use serde::{Deserialize, Serialize};
 use ui9::names::Fqn;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TracerInfo {
     // TODO: Use `Class` wrapper
     pub class: String,
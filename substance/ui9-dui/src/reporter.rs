@@ -65,17 +65,26 @@ impl Operation {
     }
 
     fn act_job(&mut self, action: JobData) {
-        let event = Act::<Job> { action };
+        let event = Act::<Job> {
+            action,
+            origin: None,
+        };
         LOG_BRIDGE.event(event);
     }
 
     fn act_event(&mut self, action: EventData) {
-        let event = Act::<Event> { action };
+        let event = Act::<Event> {
+            action,
+            origin: None,
+        };
         LOG_BRIDGE.event(event);
     }
 
     fn act_failure(&mut self, action: FailureData) {
-        let event = Act::<Failure> { action };
+        let event = Act::<Failure> {
+            action,
+            origin: None,
+        };
         LOG_BRIDGE.event(event);
     }
 }
@@ -95,6 +104,18 @@ impl Reporter {
         }
     }
 
+    /// Reports a failure that is not bound to any operation.
+    pub fn failure(message: &str) {
+        let action = FailureData {
+            message: message.into(),
+        };
+        let event = Act::<Failure> {
+            action,
+            origin: None,
+        };
+        LOG_BRIDGE.event(event);
+    }
+
     /*
     pub fn log(msg: &str) {
         let event = Act {
@@ -0,0 +1,201 @@
+//! Recording of flows traffic and its replay.
+//!
+//! A session is stored as JSON lines: the initial state of every flow
+//! and its events with milliseconds elapsed since the recording started.
+
+use crate::flow::{PackedEvent, PackedState, Raw};
+use crate::publisher::{Replay, TracerInfo};
+use crate::subscriber::{Listener, SubEvent};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::time::{Duration, Instant};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::{StreamExt, StreamMap};
+use ui9::names::Fqn;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Record {
+    State {
+        at: u64,
+        fqn: Fqn,
+        info: TracerInfo,
+        state: PackedState,
+    },
+    Event {
+        at: u64,
+        fqn: Fqn,
+        event: PackedEvent,
+    },
+    Lost {
+        at: u64,
+        fqn: Fqn,
+    },
+}
+
+impl Record {
+    pub fn at(&self) -> Duration {
+        let at = match self {
+            Self::State { at, .. } | Self::Event { at, .. } | Self::Lost { at, .. } => *at,
+        };
+        Duration::from_millis(at)
+    }
+}
+
+/// Writes records of flows with the time elapsed since its creation.
+pub struct SessionWriter<W: Write> {
+    writer: W,
+    started: Instant,
+}
+
+impl<W: Write> SessionWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            started: Instant::now(),
+        }
+    }
+
+    /// Records flows until all of them are closed.
+    pub async fn record(&mut self, flows: Vec<(Fqn, TracerInfo, Listener<Raw>)>) -> Result<()> {
+        let mut infos = HashMap::new();
+        let mut streams = StreamMap::new();
+        // Listeners keep players alive
+        let mut listeners = Vec::new();
+        for (fqn, info, mut listener) in flows {
+            let stream = UnboundedReceiverStream::new(listener.receiver()?);
+            streams.insert(fqn.clone(), stream);
+            infos.insert(fqn, info);
+            listeners.push(listener);
+        }
+        while let Some((fqn, event)) = streams.next().await {
+            let at = self.started.elapsed().as_millis() as u64;
+            let record = match event {
+                SubEvent::State(state) => Record::State {
+                    at,
+                    info: infos[&fqn].clone(),
+                    state: state.borrow().state.clone(),
+                    fqn,
+                },
+                SubEvent::Event(event) => Record::Event { at, fqn, event },
+                SubEvent::Lost => Record::Lost { at, fqn },
+            };
+            self.write(&record)?;
+        }
+        Ok(())
+    }
+
+    pub fn write(&mut self, record: &Record) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        writeln!(self.writer)?;
+        // Records are kept if the recording is interrupted
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+pub fn read_session(reader: impl BufRead) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .map_err(|err| anyhow!("Invalid record at line {}: {err}", index + 1))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Publishes recorded flows through the `HubServer` as if they were live.
+pub struct SessionPlayer {
+    prefix: Option<Fqn>,
+    flows: HashMap<Fqn, Replay>,
+}
+
+impl SessionPlayer {
+    /// The prefix is added to names of flows to avoid conflicts with local ones.
+    pub fn new(prefix: Option<Fqn>) -> Self {
+        Self {
+            prefix,
+            flows: HashMap::new(),
+        }
+    }
+
+    /// Plays records keeping their timing divided by the `speed`.
+    /// Flows are still published when the playback is finished.
+    pub async fn play(&mut self, records: Vec<Record>, speed: f64) -> Result<()> {
+        if speed.is_nan() || speed <= 0.0 {
+            return Err(anyhow!("The speed of the replay must be positive"));
+        }
+        let started = tokio::time::Instant::now();
+        for record in records {
+            let delay = record.at().div_f64(speed);
+            tokio::time::sleep_until(started + delay).await;
+            match record {
+                Record::State {
+                    fqn, info, state, ..
+                } => {
+                    let fqn = self.scoped(fqn);
+                    let replay = Replay::new(fqn.clone(), info, state);
+                    self.flows.insert(fqn, replay);
+                }
+                Record::Event { fqn, event, .. } => {
+                    let fqn = self.scoped(fqn);
+                    match self.flows.get(&fqn) {
+                        Some(replay) => replay.event(event),
+                        None => log::warn!("An event of {fqn} has no state to replay"),
+                    }
+                }
+                Record::Lost { fqn, .. } => {
+                    let fqn = self.scoped(fqn);
+                    self.flows.remove(&fqn);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn scoped(&self, fqn: Fqn) -> Fqn {
+        match &self.prefix {
+            Some(prefix) => Fqn::from_iter(prefix.iter().chain(fqn.iter())),
+            None => fqn,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_written_session() {
+        let fqn = Fqn::from_iter(["agent", "chat"]);
+        let records = vec![
+            Record::State {
+                at: 0,
+                fqn: fqn.clone(),
+                info: TracerInfo {
+                    class: "Chat".into(),
+                },
+                state: PackedState(vec![1, 2, 3]),
+            },
+            Record::Event {
+                at: 1500,
+                fqn: fqn.clone(),
+                event: PackedEvent(vec![4]),
+            },
+            Record::Lost { at: 2000, fqn },
+        ];
+        let mut writer = SessionWriter::new(Vec::new());
+        for record in &records {
+            writer.write(record).unwrap();
+        }
+        let read = read_session(writer.writer.as_slice()).unwrap();
+        assert_eq!(read, records);
+        assert_eq!(read[1].at(), Duration::from_millis(1500));
+    }
+}
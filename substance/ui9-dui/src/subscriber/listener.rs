@@ -19,6 +19,14 @@ impl<F: Flow> Listener<F> {
         Self::new::<LocalPlayer<F>>((), fqn)
     }
 
+    /// The listener that only sends actions. The events channel is closed,
+    /// so the player doesn't keep events that nobody reads.
+    pub fn local_actions(fqn: Fqn) -> Self {
+        let mut listener = Self::local(fqn);
+        listener.event_rx.take();
+        listener
+    }
+
     pub fn new<P: Player<F>>(args: P::Args, fqn: Fqn) -> Self {
         let (event_tx, event_rx) = mpsc::unbounded_channel();
         let state = PlayerState {
@@ -47,7 +55,10 @@ impl<F: Flow> Listener<F> {
     }
 
     pub fn action(&self, action: F::Action) {
-        let msg = Act { action };
+        let msg = Act {
+            action,
+            origin: None,
+        };
         self.player.send(msg).ok();
     }
 }
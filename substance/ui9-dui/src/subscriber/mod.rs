@@ -35,6 +35,19 @@ impl<P: Subscriber> Sub<P> {
         Self::local(P::fqn())
     }
 
+    /// The handle that only sends actions to the flow.
+    pub fn local_actions(fqn: Fqn) -> Self {
+        let listener = Listener::<P>::local_actions(fqn);
+        Self::new(listener)
+    }
+
+    pub fn local_unified_actions() -> Self
+    where
+        P: Unified,
+    {
+        Self::local_actions(P::fqn())
+    }
+
     pub fn new(listener: Listener<P>) -> Self {
         Self {
             driver: P::Driver::from(listener),
@@ -106,6 +119,8 @@ impl<F: Flow> PlayerState<F> {
 
 pub struct Act<F: Flow> {
     pub action: F::Action,
+    /// The peer that sent the action, `None` if it's sent locally
+    pub origin: Option<String>,
 }
 
 pub trait Player<F: Flow>: Agent<Context: Default> + OnEvent<Act<F>> {
@@ -87,6 +87,14 @@ impl Level {
         level
     }
 
+    pub fn find(&self, fqn: &Fqn) -> Option<&Level> {
+        let mut level = self;
+        for segment in fqn.iter() {
+            level = level.levels.get(segment)?;
+        }
+        Some(level)
+    }
+
     pub fn remove(&mut self, fqn: &Fqn) {
         self.remove_path(fqn.as_ref());
     }
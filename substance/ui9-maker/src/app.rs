@@ -8,6 +8,7 @@ use crb::core::{mpsc, Slot};
 use crb::runtime::InteractiveRuntime;
 use crb::superagent::{Drainer, Supervisor, SupervisorSession};
 use std::collections::BTreeMap;
+use tokio_stream::{wrappers::UnboundedReceiverStream, StreamExt};
 use ui9_dui::subscriber::{drainer, SubEvent};
 use ui9_dui::tracers::tree::Tree;
 use ui9_dui::Sub;
@@ -86,13 +87,23 @@ impl App {
         if !self.trees.contains_key(&peer) {
             let mut sub = Sub::<Tree>::remote_unified(peer);
 
-            let events = sub.events()?;
-            ctx.assign(events, (), ());
+            let stream = UnboundedReceiverStream::new(sub.receiver()?)
+                .map(move |event| TreeEvent { peer, event });
+            ctx.assign(Drainer::new(stream), (), ());
 
             self.trees.insert(peer, sub);
         }
         Ok(())
     }
+
+    fn unsubscribe_from_peer(&mut self, peer: PeerId) -> Result<()> {
+        if self.trees.remove(&peer).is_some() {
+            log::info!("Unsubscribing from peer's tree: {peer}");
+            let ui_event = UiEvent::DelTree { peer };
+            self.ui_events_tx.send(ui_event)?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -111,6 +122,9 @@ impl OnEvent<SubEvent<Peer>> for App {
                     PeerEvent::AddPeer { peer_id, .. } => {
                         self.subscribe_to_peer(*peer_id, ctx)?;
                     }
+                    PeerEvent::DelPeer { peer_id } => {
+                        self.unsubscribe_from_peer(*peer_id)?;
+                    }
                     _ => {}
                 }
                 let ui_event = UiEvent::StateChanged;
@@ -122,16 +136,27 @@ impl OnEvent<SubEvent<Peer>> for App {
     }
 }
 
+struct TreeEvent {
+    peer: PeerId,
+    event: SubEvent<Tree>,
+}
+
 #[async_trait]
-impl OnEvent<SubEvent<Tree>> for App {
-    async fn handle(&mut self, event: SubEvent<Tree>, _ctx: &mut Context<Self>) -> Result<()> {
-        match event {
-            SubEvent::State(state) => {
-                let tree = state.borrow();
-                log::info!("TREE: {tree:?}");
+impl OnEvent<TreeEvent> for App {
+    async fn handle(&mut self, msg: TreeEvent, _ctx: &mut Context<Self>) -> Result<()> {
+        let ui_event = match msg.event {
+            SubEvent::State(tree) => UiEvent::SetTree {
+                peer: msg.peer,
+                tree,
+            },
+            SubEvent::Event(_) => UiEvent::StateChanged,
+            SubEvent::Lost => {
+                // The last tree is kept until the peer is gone
+                log::warn!("The tree of {} is lost", msg.peer);
+                return Ok(());
             }
-            _ => {}
-        }
+        };
+        self.ui_events_tx.send(ui_event)?;
         Ok(())
     }
 }
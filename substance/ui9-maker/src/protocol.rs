@@ -1,10 +1,22 @@
 use ui9_dui::subscriber::State;
-use ui9_net::tracers::peer::Peer;
+use ui9_dui::tracers::tree::Tree;
+use ui9_net::tracers::peer::{Peer, PeerId};
 
 /// Ad event sent from `App` to `Ui`
 pub enum UiEvent {
-    SetState { peers: State<Peer> },
+    SetState {
+        peers: State<Peer>,
+    },
     StateChanged,
+    /// The flow tree of a peer is available
+    SetTree {
+        peer: PeerId,
+        tree: State<Tree>,
+    },
+    /// The peer has gone with its tree
+    DelTree {
+        peer: PeerId,
+    },
 }
 
 /// Ad event sent from `Ui` to `App`
@@ -0,0 +1,62 @@
+//! Flows that are relayed only to allowed peers.
+//!
+//! Flows are open to all peers of the mesh unless they are restricted here.
+
+use crate::service::AllowedPeers;
+use anyhow::Result;
+use libp2p::PeerId;
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use ui9::names::Fqn;
+
+static RESTRICTED: LazyLock<RwLock<HashMap<Fqn, AllowedPeers>>> = LazyLock::new(Default::default);
+
+/// Relays the flow only to the peers, an empty set rejects all of them.
+pub fn restrict(fqn: Fqn, peers: AllowedPeers) {
+    let mut restricted = RESTRICTED
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    restricted.insert(fqn, peers);
+}
+
+/// Opens the flow to all peers again.
+pub fn open(fqn: &Fqn) {
+    let mut restricted = RESTRICTED
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    restricted.remove(fqn);
+}
+
+/// Checks the peer could subscribe to the flow.
+pub fn check(fqn: &Fqn, peer: &PeerId) -> Result<()> {
+    let restricted = RESTRICTED
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match restricted.get(fqn) {
+        Some(peers) => peers.check(peer),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restricted_flow() {
+        let fqn = Fqn::root("@restricted");
+        let peer = PeerId::random();
+        assert!(check(&fqn, &peer).is_ok());
+
+        restrict(fqn.clone(), AllowedPeers::default());
+        assert!(check(&fqn, &peer).is_err());
+
+        let allowed = AllowedPeers::parse(&[peer.to_string()]).unwrap();
+        restrict(fqn.clone(), allowed);
+        assert!(check(&fqn, &peer).is_ok());
+        assert!(check(&fqn, &PeerId::random()).is_err());
+
+        open(&fqn);
+        assert!(check(&fqn, &PeerId::random()).is_ok());
+    }
+}
@@ -1,5 +1,7 @@
+pub mod access;
 mod relay;
 mod remote;
+pub mod service;
 pub mod tracers;
 
 pub use relay::MeshNode;
@@ -7,6 +7,7 @@ mod relay_player;
 mod remote_player;
 mod router;
 
+pub(crate) use flex::FlexCodec;
 pub use node::MeshNode;
 pub use remote_player::RemotePlayer;
 
@@ -1,17 +1,20 @@
 use super::drainer::{from_stream, MessageSink};
 use super::protocol::{Ui9Message, Ui9Request, Ui9Response};
+use crate::access;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use crb::agent::{Agent, AgentSession, Context, DoAsync, ManagedContext, Next, OnEvent};
 use crb::core::Slot;
 use crb::superagent::{Entry, Supervisor, SupervisorSession};
 use futures::SinkExt;
-use libp2p::Stream;
+use libp2p::{PeerId, Stream};
 use ui9_dui::flow::PackedEvent;
 use ui9_dui::hub::Hub;
 use ui9_dui::publisher::{EventFlow, RecorderLink};
 
 pub struct RelayPlayer {
+    peer: PeerId,
+
     // State 1
     stream: Slot<Stream>,
 
@@ -24,8 +27,9 @@ pub struct RelayPlayer {
 }
 
 impl RelayPlayer {
-    pub fn new(stream: Stream) -> Self {
+    pub fn new(peer: PeerId, stream: Stream) -> Self {
         Self {
+            peer,
             stream: Slot::filled(stream),
             writer: Slot::empty(),
             entry: Slot::empty(),
@@ -71,6 +75,11 @@ impl OnEvent<Result<Ui9Message>> for RelayPlayer {
                         if self.entry.is_filled() {
                             return Err(anyhow!("Trying to subscribe twice"));
                         }
+                        if let Err(err) = access::check(&fqn, &self.peer) {
+                            log::warn!("The subscription to {fqn} is rejected: {err}");
+                            ctx.shutdown();
+                            return Ok(());
+                        }
                         // Subscribing to events stream
                         let hub = Hub::link()?;
                         let mut recorder = hub.server.discover(fqn).await?;
@@ -83,7 +92,7 @@ impl OnEvent<Result<Ui9Message>> for RelayPlayer {
                     }
                     Ui9Request::Action(action) => {
                         let recorder = self.recorder.get_mut()?;
-                        recorder.act(action).await?;
+                        recorder.act_from(action, self.peer.to_string()).await?;
                     }
                     Ui9Request::Unsubscribe => {
                         ctx.shutdown();
@@ -46,7 +46,8 @@ impl DoAsync<Initialize> for Router {
 #[async_trait]
 impl OnEvent<(PeerId, Stream)> for Router {
     async fn handle(&mut self, event: (PeerId, Stream), ctx: &mut Context<Self>) -> Result<()> {
-        let relay = RelayPlayer::new(event.1);
+        let (peer, stream) = event;
+        let relay = RelayPlayer::new(peer, stream);
         ctx.spawn_agent(relay, ());
         Ok(())
     }
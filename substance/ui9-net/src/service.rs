@@ -0,0 +1,170 @@
+//! Request-response services of mesh peers.
+//!
+//! Every call opens its own stream, so the response is delivered only to the caller
+//! and the server knows which peer has sent the request.
+
+use crate::relay::{FlexCodec, MeshNode};
+use anyhow::{anyhow, Error, Result};
+use futures::future;
+use futures::stream::{BoxStream, SinkExt, StreamExt};
+pub use libp2p::StreamProtocol;
+use libp2p::{PeerId, Stream};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_util::codec::Framed;
+use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt};
+use ui9_dui::flow::flow::DataFraction;
+
+/// Peers that don't send a request in time are disconnected.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The number of incoming streams that are read concurrently.
+const MAX_INCOMING: usize = 16;
+
+pub trait Service: Send + 'static {
+    const PROTOCOL: StreamProtocol;
+
+    type Request: DataFraction;
+    type Response: DataFraction;
+}
+
+#[derive(Serialize, Deserialize)]
+enum ServiceMessage<Req, Res> {
+    Request(Req),
+    Response(Result<Res, String>),
+}
+
+type Message<S> = ServiceMessage<<S as Service>::Request, <S as Service>::Response>;
+
+type ServiceStream<S> = Framed<Compat<Stream>, FlexCodec<Message<S>>>;
+
+fn framed<S: Service>(stream: Stream) -> ServiceStream<S> {
+    Framed::new(stream.compat(), FlexCodec::new())
+}
+
+/// Sends the request to the peer and waits for the response.
+/// The call fails if the response is not received in time.
+pub async fn call<S: Service>(
+    peer: PeerId,
+    request: S::Request,
+    time_limit: Duration,
+) -> Result<S::Response> {
+    let exchange = async move {
+        let node = MeshNode::link()?;
+        let mut control = node.connector.get_control().await?;
+        let stream = control.open_stream(peer, S::PROTOCOL).await?;
+        let mut stream = framed::<S>(stream);
+        stream.send(ServiceMessage::Request(request)).await?;
+        match stream.next().await.transpose()? {
+            Some(ServiceMessage::Response(response)) => response.map_err(Error::msg),
+            Some(ServiceMessage::Request(_)) => Err(anyhow!("Unexpected request from {peer}")),
+            None => Err(anyhow!("The stream is closed by {peer}")),
+        }
+    };
+    timeout(time_limit, exchange)
+        .await
+        .map_err(|_| anyhow!("The request to {peer} is timed out"))?
+}
+
+/// A request received from a peer.
+pub struct Incoming<S: Service> {
+    pub peer: PeerId,
+    pub request: S::Request,
+    pub responder: ServiceResponder<S>,
+}
+
+/// Sends the response back to the stream of the caller.
+pub struct ServiceResponder<S: Service> {
+    stream: ServiceStream<S>,
+}
+
+impl<S: Service> ServiceResponder<S> {
+    /// The response is sent in the background to not block the server.
+    pub fn send(mut self, response: Result<S::Response>) {
+        let message = ServiceMessage::Response(response.map_err(|err| err.to_string()));
+        crb::core::spawn(async move {
+            if let Err(err) = self.stream.send(message).await {
+                log::error!("Can't send a response of {}: {err}", S::PROTOCOL);
+            }
+        });
+    }
+}
+
+/// Accepts requests of the service sent by peers.
+/// Checking that the peer is allowed to call the service is up to the server.
+pub async fn serve<S: Service>() -> Result<BoxStream<'static, Incoming<S>>> {
+    let node = MeshNode::link()?;
+    let mut control = node.connector.get_control().await?;
+    let streams = control.accept(S::PROTOCOL)?;
+    let requests = streams
+        .map(|(peer, stream)| async move {
+            let mut stream = framed::<S>(stream);
+            match timeout(REQUEST_TIMEOUT, stream.next()).await {
+                Ok(Some(Ok(ServiceMessage::Request(request)))) => Some(Incoming {
+                    peer,
+                    request,
+                    responder: ServiceResponder { stream },
+                }),
+                _ => {
+                    log::warn!("The request of {peer} to {} is dropped", S::PROTOCOL);
+                    None
+                }
+            }
+        })
+        .buffer_unordered(MAX_INCOMING)
+        .filter_map(future::ready)
+        .boxed();
+    Ok(requests)
+}
+
+/// Peers allowed to call a service.
+#[derive(Debug, Clone, Default)]
+pub struct AllowedPeers {
+    peers: BTreeSet<PeerId>,
+}
+
+impl AllowedPeers {
+    /// Parses ids of peers, e.g. from a config.
+    pub fn parse(peers: &[String]) -> Result<Self> {
+        let peers = peers
+            .iter()
+            .map(|peer| {
+                peer.parse()
+                    .map_err(|err| anyhow!("Invalid peer id {peer}: {err}"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { peers })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    pub fn contains(&self, peer: &PeerId) -> bool {
+        self.peers.contains(peer)
+    }
+
+    pub fn check(&self, peer: &PeerId) -> Result<()> {
+        if self.contains(peer) {
+            Ok(())
+        } else {
+            Err(anyhow!("The peer {peer} is not allowed"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowed_peers() {
+        let peer = PeerId::random();
+        let allowed = AllowedPeers::parse(&[peer.to_string()]).unwrap();
+        assert!(allowed.check(&peer).is_ok());
+        assert!(allowed.check(&PeerId::random()).is_err());
+        assert!(AllowedPeers::parse(&["not a peer".into()]).is_err());
+    }
+}
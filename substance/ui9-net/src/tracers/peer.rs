@@ -6,7 +6,7 @@ use std::num::ParseIntError;
 use ui9::names::Fqn;
 use ui9_dui::flow::{Flow, Unified};
 use ui9_dui::publisher::{Publisher, Tracer};
-use ui9_dui::subscriber::{Listener, Subscriber};
+use ui9_dui::subscriber::{Listener, SubEvent, Subscriber};
 
 #[derive(Deref, DerefMut, From, Into)]
 pub struct PeerSub {
@@ -118,3 +118,75 @@ pub enum PeerEvent {
         connection: ConnectionId,
     },
 }
+
+/// Peers known by a subscriber of the `Peer` flow.
+#[derive(Debug, Default)]
+pub struct PeerSet {
+    peers: BTreeSet<PeerId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerChange {
+    Added(PeerId),
+    Removed(PeerId),
+}
+
+impl PeerSet {
+    /// Applies the event of the subscription and returns changes of the set.
+    /// All peers are removed if the subscription is lost.
+    pub fn update(&mut self, event: SubEvent<Peer>) -> Vec<PeerChange> {
+        match event {
+            SubEvent::State(state) => {
+                let peers: BTreeSet<_> = state.borrow().peers.keys().cloned().collect();
+                let removed = self.peers.difference(&peers).cloned();
+                let mut changes: Vec<_> = removed.map(PeerChange::Removed).collect();
+                let added = peers.difference(&self.peers).cloned();
+                changes.extend(added.map(PeerChange::Added));
+                self.peers = peers;
+                changes
+            }
+            SubEvent::Event(PeerEvent::AddPeer { peer_id }) if self.peers.insert(peer_id) => {
+                vec![PeerChange::Added(peer_id)]
+            }
+            SubEvent::Event(PeerEvent::DelPeer { peer_id }) if self.peers.remove(&peer_id) => {
+                vec![PeerChange::Removed(peer_id)]
+            }
+            SubEvent::Event(_) => Vec::new(),
+            SubEvent::Lost => {
+                let peers = std::mem::take(&mut self.peers);
+                peers.into_iter().map(PeerChange::Removed).collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ui9_dui::State;
+
+    #[test]
+    fn test_peer_set_changes() {
+        let (first, second) = (PeerId::random(), PeerId::random());
+        let mut set = PeerSet::default();
+
+        let event = SubEvent::Event(PeerEvent::AddPeer { peer_id: first });
+        assert_eq!(set.update(event), vec![PeerChange::Added(first)]);
+        let event = SubEvent::Event(PeerEvent::AddPeer { peer_id: first });
+        assert!(set.update(event).is_empty());
+
+        let mut peer = Peer::default();
+        peer.peers.insert(second, PeerRecord::default());
+        let (state, _state_tx) = State::new(peer);
+        let changes = set.update(SubEvent::State(state));
+        assert_eq!(
+            changes,
+            vec![PeerChange::Removed(first), PeerChange::Added(second)]
+        );
+
+        assert_eq!(
+            set.update(SubEvent::Lost),
+            vec![PeerChange::Removed(second)]
+        );
+    }
+}